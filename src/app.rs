@@ -1,4 +1,5 @@
-use crate::{configuration::Settings, task::Task, utils};
+use crate::{configuration::Settings, status::Status, task::Task, utils};
+use anyhow::Result;
 
 pub struct App {
     pub tasks: Vec<Task>,
@@ -10,15 +11,21 @@ impl App {
     pub fn new(settings: Settings) -> App {
         let tasks = utils::load_tasks(&settings.db_file);
         let current_id = tasks.iter().map(|t| t.id.unwrap()).max().unwrap_or(0);
-        App {
+        let mut app = App {
             tasks,
             settings,
             current_id,
-        }
+        };
+        app.sort_tasks();
+        app
+    }
+
+    pub fn sort_tasks(&mut self) {
+        self.settings.get_sort_order().sort(&mut self.tasks);
     }
 
     pub fn save_state(&mut self) {
-        self.tasks.sort_by(|a, b| a.date.cmp(&b.date));
+        self.sort_tasks();
         utils::save_tasks(&self.settings.db_file, &self);
     }
 
@@ -33,38 +40,145 @@ impl App {
         t
     }
 
+    pub fn update_task(&mut self, t: Task) -> Option<Task> {
+        let idx = self.tasks.iter().position(|task| task.id == t.id)?;
+        self.tasks[idx] = t.clone();
+        self.save_state();
+        Some(t)
+    }
+
+    /// Applies `f` to every task in `ids` with a single save
+    pub fn update_tasks<F: FnMut(&mut Task)>(&mut self, ids: &[usize], mut f: F) -> Vec<Task> {
+        let mut updated = vec![];
+        for task in self.tasks.iter_mut().filter(|t| ids.contains(&t.get_id())) {
+            f(task);
+            updated.push(task.clone());
+        }
+        self.save_state();
+        updated
+    }
+
+    /// Puts back tasks from before a change
+    pub fn restore_tasks(&mut self, tasks: Vec<Task>) {
+        self.tasks = tasks;
+        self.save_state();
+    }
+
     pub fn delete_task(&mut self, id: usize) -> Option<Task> {
-        let deleted_idx = self.tasks.iter().position(|t| t.id.unwrap() == id)?;
-        let deleted_task = self.tasks.get(deleted_idx).cloned();
-        self.tasks.remove(deleted_idx);
+        self.delete_tasks(&[id]).pop()
+    }
+
+    /// Deletes every task in `ids` with a single save
+    pub fn delete_tasks(&mut self, ids: &[usize]) -> Vec<Task> {
+        let (deleted, kept) = self
+            .tasks
+            .drain(..)
+            .partition(|t| ids.contains(&t.get_id()));
+        self.tasks = kept;
         self.save_state();
-        deleted_task
+        deleted
     }
 
-    pub fn set_complete(&mut self, id: usize, complete: bool) -> Option<Task> {
-        let idx = self.tasks.iter().position(|t| t.id.unwrap() == id)?;
-        let new_task;
-        if complete {
-            let possible_new_task = self.tasks[idx].set_complete();
-            if let Some(possible_new_task) = possible_new_task {
-                new_task = self.add_task(possible_new_task);
-                self.delete_task(id);
-            } else {
-                new_task = self.tasks[idx].clone();
+    pub fn set_status(&mut self, id: usize, status: Status) -> Option<Task> {
+        self.set_statuses(&[id], status).pop()
+    }
+
+    /// Changes the status of every task in `ids` with a single save
+    pub fn set_statuses(&mut self, ids: &[usize], status: Status) -> Vec<Task> {
+        let mut updated = vec![];
+        for &id in ids {
+            let idx = match self.tasks.iter().position(|t| t.get_id() == id) {
+                Some(idx) => idx,
+                None => continue,
+            };
+
+            // Completing a repeating task replaces it with its next occurrence
+            if let Some(mut new_task) = self.tasks[idx].set_status(status) {
+                new_task.id = Some(self.get_next_id());
+                self.tasks[idx] = new_task;
             }
-        } else {
-            self.tasks[idx].set_incomplete();
-            new_task = self.tasks[idx].clone();
+            updated.push(self.tasks[idx].clone());
         }
 
         self.save_state();
-        Some(new_task)
+        updated
     }
 
     pub fn toggle_complete_task(&mut self, id: usize) -> Option<Task> {
         let idx = self.tasks.iter().position(|t| t.id.unwrap() == id).unwrap();
-        let complete = self.tasks[idx].complete;
-        self.set_complete(id, !complete)
+        let status = if self.tasks[idx].is_complete() {
+            Status::Todo
+        } else {
+            Status::Complete
+        };
+        self.set_status(id, status)
+    }
+
+    pub fn cycle_status_task(&mut self, id: usize) -> Option<Task> {
+        let idx = self.tasks.iter().position(|t| t.id.unwrap() == id).unwrap();
+        let status = self.tasks[idx].status.next();
+        self.set_status(id, status)
+    }
+
+    /// Snoozes every task in `ids` with a spec understood by `utils::parse_relative_date`
+    pub fn snooze_tasks(&mut self, ids: &[usize], spec: &str) -> Result<Vec<Task>> {
+        // Parse every date first so a bad spec leaves all tasks untouched
+        let dates = self
+            .tasks
+            .iter()
+            .filter(|t| ids.contains(&t.get_id()))
+            .map(|t| utils::parse_relative_date(spec, &t.date, &self.settings))
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut snoozed = vec![];
+        let tasks = self.tasks.iter_mut().filter(|t| ids.contains(&t.get_id()));
+        for (task, date) in tasks.zip(dates) {
+            task.reschedule(date);
+            snoozed.push(task.clone());
+        }
+        self.save_state();
+        Ok(snoozed)
+    }
+
+    pub fn overdue_task_ids(&self) -> Vec<usize> {
+        self.tasks
+            .iter()
+            .filter(|t| t.is_overdue())
+            .map(|t| t.get_id())
+            .collect()
+    }
+
+    /// Starts tracking time on a task, stopping any other running timer
+    pub fn start_timer(&mut self, id: usize) -> Option<Task> {
+        let idx = self.tasks.iter().position(|t| t.id.unwrap() == id)?;
+        for task in self.tasks.iter_mut() {
+            task.stop_timer();
+        }
+        self.tasks[idx].start_timer();
+        let task = self.tasks[idx].clone();
+        self.save_state();
+        Some(task)
+    }
+
+    /// Stops every running timer and returns the tasks that had one
+    pub fn stop_timers(&mut self) -> Vec<Task> {
+        let mut stopped = vec![];
+        for task in self.tasks.iter_mut().filter(|t| t.is_timer_running()) {
+            task.stop_timer();
+            stopped.push(task.clone());
+        }
+        self.save_state();
+        stopped
+    }
+
+    pub fn toggle_timer_task(&mut self, id: usize) -> Option<Task> {
+        let task = self.get_task(id)?;
+        if task.is_timer_running() {
+            self.stop_timers();
+            self.get_task(id).cloned()
+        } else {
+            self.start_timer(id)
+        }
     }
 
     fn get_next_id(&mut self) -> usize {
@@ -4,6 +4,7 @@ use clap::Parser;
 use crate::app::App;
 use crate::cli::cli_utils;
 use crate::cli::formats::Format;
+use crate::priority::Priority;
 use crate::task_form::TaskForm;
 
 #[derive(Parser)]
@@ -13,12 +14,33 @@ pub struct Args {
     /// The date the task is due
     #[arg(long)]
     date: Option<String>,
+    /// The date the task becomes actionable. It is hidden until then
+    #[arg(long)]
+    scheduled: Option<String>,
     /// How often the task repeats
     #[arg(long)]
     repeats: Option<String>,
     /// A description or url for your task
     #[arg(long)]
     description: Option<String>,
+    /// Comma separated tags for your task
+    #[arg(long)]
+    tags: Option<String>,
+    /// How long the task should take, e.g. 1h30m
+    #[arg(long)]
+    estimate: Option<String>,
+    /// Comma separated reminders before the due date, e.g. 15m,1d
+    #[arg(long)]
+    reminders: Option<String>,
+    /// The project the task belongs to
+    #[arg(long)]
+    project: Option<String>,
+    /// How important the task is
+    #[arg(long)]
+    priority: Option<Priority>,
+    /// Comma separated URLs, file paths or mailto: addresses, besides the ones in the description
+    #[arg(long)]
+    links: Option<String>,
     /// The format to display the new task with
     #[arg(long)]
     format: Option<Format>,
@@ -29,15 +51,32 @@ pub fn run(mut app: App, args: Args) -> Result<()> {
         name,
         format,
         date,
+        scheduled,
         repeats,
         description,
+        tags,
+        estimate,
+        reminders,
+        project,
+        priority,
+        links,
     } = args;
     let mut task_form = TaskForm {
         name,
         date: date.unwrap_or("".to_string()),
+        scheduled: scheduled.unwrap_or("".to_string()),
         repeats: repeats.unwrap_or("".to_string()),
         description: description.unwrap_or("".to_string()),
+        tags: tags.unwrap_or("".to_string()),
+        estimate: estimate.unwrap_or("".to_string()),
+        reminders: reminders.unwrap_or("".to_string()),
+        project: project.unwrap_or("".to_string()),
+        priority: priority.map(|p| p.to_string()).unwrap_or("".to_string()),
+        links: links.unwrap_or("".to_string()),
     };
+    if let Some(view) = app.settings.get_context() {
+        view.apply_context(&mut task_form);
+    }
     let task = task_form.submit(&app.settings)?;
     let task = app.add_task(task);
 
@@ -1,5 +1,8 @@
 use super::formats::Format;
+use crate::theme::{self, Theme};
 use crate::{configuration::Settings, task::Task, utils};
+use std::io::IsTerminal;
+use tui::style::Style;
 
 pub fn print_task(task: &Task, format: Option<Format>, settings: &Settings) {
     match format {
@@ -7,11 +10,36 @@ pub fn print_task(task: &Task, format: Option<Format>, settings: &Settings) {
         _ => {
             println!("{} {}", task.id.unwrap(), task.name);
             println!("Date: {}", utils::date_to_display_str(&task.date, &settings));
+            if let Some(scheduled) = &task.scheduled {
+                println!("Scheduled: {}", utils::start_date_to_display_str(scheduled, settings));
+            }
             println!("Repeats: {:}", task.repeats);
             if let Some(description) = &task.description {
                 println!("Description: {}", description);
             }
-            println!("complete: {}", settings.icons.get_complete_icon(task.complete));
+            for (idx, link) in task.all_links().iter().enumerate() {
+                println!("Link {}: {}", idx + 1, link);
+            }
+            if let Some(project) = &task.project {
+                println!("Project: {}", project);
+            }
+            if let Some(priority) = &task.priority {
+                println!("Priority: {}", priority);
+            }
+            if !task.tags.is_empty() {
+                println!("Tags: {}", task.tags.join(", "));
+            }
+            if let Some(estimate) = &task.estimate {
+                println!("Estimate: {}", utils::format_duration(estimate));
+            }
+            if !task.reminders.is_empty() {
+                println!("Reminders: {} before", utils::format_durations(&task.reminders));
+            }
+            if !task.time_entries.is_empty() {
+                let running = if task.is_timer_running() { " (running)" } else { "" };
+                println!("Time tracked: {}{}", utils::format_duration(&task.total_time()), running);
+            }
+            println!("Status:{} {}", settings.icons.get_status_icon(task.status), task.status);
         }
     }
 }
@@ -20,16 +48,36 @@ pub fn print_tasks(tasks: Vec<&Task>, format: Option<Format>, settings: &Setting
     match format {
         Some(Format::Json) => println!("{}", serde_json::to_string_pretty(&tasks).unwrap()),
         _ => {
+            // Only color terminals, not pipes and files
+            let theme = match std::io::stdout().is_terminal() && !theme::no_color() {
+                true => Some(Theme::new(&settings.theme).unwrap_or_default()),
+                false => None,
+            };
             for task in tasks {
                 let id = task.id.unwrap();
                 let name = utils::ellipsis_or_pad(&task.name, 20);
                 let date = utils::date_to_display_str(&task.date, &settings);
                 let repeats = &task.repeats;
-                let complete = task.complete;
-
-                let x = settings.icons.get_complete_icon(complete);
-                println!("{} {} ({})\t{}\t{}", x, name, id, date, repeats);
+                let x = settings.icons.get_status_icon(task.status);
+                let line = format!("{} {} ({})\t{}\t{}", x, name, id, date, repeats);
+                match &theme {
+                    Some(theme) => println!("{}", theme::paint(&line, task_style(task, theme, settings))),
+                    None => println!("{}", line),
+                }
             }
         }
     }
 }
+
+/// Highlights closed, overdue and soon due tasks like the TUI does
+fn task_style(task: &Task, theme: &Theme, settings: &Settings) -> Style {
+    if task.status.is_closed() {
+        theme.complete
+    } else if task.is_overdue() {
+        theme.overdue
+    } else if settings.is_due_soon(task) {
+        theme.due_soon
+    } else {
+        Style::default()
+    }
+}
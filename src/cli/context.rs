@@ -0,0 +1,41 @@
+use crate::app::App;
+use anyhow::Result;
+use clap::Parser;
+
+#[derive(Parser)]
+pub struct Args {
+    /// The view to switch to. Shows the active view and the available ones if missing
+    name: Option<String>,
+    /// Clears the active view
+    #[arg(short, long, conflicts_with = "name")]
+    clear: bool,
+}
+
+pub fn run(mut app: App, args: Args) -> Result<()> {
+    let Args { name, clear } = args;
+
+    if clear {
+        app.settings.set_context(None);
+        println!("Cleared the active view");
+        return Ok(());
+    }
+
+    if let Some(name) = name {
+        app.settings.get_view(&name)?;
+        app.settings.set_context(Some(name.clone()));
+        println!("Switched to view {}", name);
+        return Ok(());
+    }
+
+    if app.settings.views.is_empty() {
+        println!("No views defined. Add them to \"views\" in the config");
+        return Ok(());
+    }
+
+    for name in app.settings.views.keys() {
+        let active = if app.settings.context.as_ref() == Some(name) { "*" } else { " " };
+        println!("{} {}", active, name);
+    }
+
+    Ok(())
+}
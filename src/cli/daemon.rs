@@ -0,0 +1,49 @@
+use crate::app::App;
+use crate::configuration::Settings;
+use crate::reminders::{self, DeliveredReminders};
+use crate::utils;
+use anyhow::Result;
+use chrono::Local;
+use clap::Parser;
+use std::thread;
+use std::time::Duration;
+
+#[derive(Parser)]
+pub struct Args {
+    /// How often to check for reminders, in seconds
+    #[arg(long, default_value_t = 30)]
+    interval: u64,
+}
+
+pub fn run(app: App, args: Args) -> Result<()> {
+    let Args { interval } = args;
+    let mut delivered = DeliveredReminders::load(Settings::get_reminders_file());
+    println!("Watching {} for reminders", app.settings.db_file);
+
+    loop {
+        // Reload every time so changes from the TUI and CLI are picked up
+        let tasks = match utils::try_load_tasks(&app.settings.db_file) {
+            Ok(tasks) => tasks,
+            Err(e) => {
+                eprintln!("Unable to read tasks, retrying next time: {:#}", e);
+                thread::sleep(Duration::from_secs(interval));
+                continue;
+            }
+        };
+        let now = Local::now();
+
+        for reminder in reminders::due_reminders(&tasks, now, &delivered) {
+            match reminders::notify(&reminder, &app.settings) {
+                Ok(()) => {
+                    println!("Sent reminder for {} ({})", reminder.task.name, reminder.task.get_id());
+                    delivered.insert(&reminder);
+                    delivered.save();
+                }
+                Err(e) => eprintln!("Unable to send reminder for {}: {}", reminder.task.name, e),
+            }
+        }
+
+        delivered.prune(now);
+        thread::sleep(Duration::from_secs(interval));
+    }
+}
@@ -1,28 +1,34 @@
-use super::{cli_utils, formats::Format};
+use super::{cli_utils, formats::Format, selection::Selection};
 use crate::app::App;
 use anyhow::Result;
 use clap::Parser;
 
 #[derive(Parser)]
 pub struct Args {
-    /// The ID of the task to delete
-    #[arg(short, long)]
-    id: usize,
-    /// The format to print the deleted task with
+    #[command(flatten)]
+    selection: Selection,
+    /// The format to print the deleted tasks with
     #[arg(short, long)]
     format: Option<Format>,
 }
 
 pub fn run(mut app: App, args: Args) -> Result<()> {
-    let Args { id, format } = args;
-    let task = app.delete_task(id);
+    let Args { selection, format } = args;
+    let ids = selection.resolve(&app)?;
 
-    if task.is_none() {
-        println!("Task with id {} not found", id);
+    if ids.is_empty() {
+        println!("No tasks to delete");
+        return Ok(());
+    }
+    if !selection.confirm("delete", &ids, &app)? {
+        return Ok(());
     }
 
-    let task = task.unwrap();
-    cli_utils::print_task(&task, format, &app.settings);
+    let tasks = app.delete_tasks(&ids);
+    match tasks.as_slice() {
+        [task] => cli_utils::print_task(task, format, &app.settings),
+        _ => cli_utils::print_tasks(tasks.iter().collect(), format, &app.settings),
+    }
 
     Ok(())
 }
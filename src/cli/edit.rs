@@ -0,0 +1,106 @@
+use anyhow::{anyhow, Result};
+use clap::Parser;
+
+use crate::app::App;
+use crate::cli::cli_utils;
+use crate::cli::formats::Format;
+use crate::editor;
+use crate::priority::Priority;
+use crate::task_form::TaskForm;
+
+#[derive(Parser)]
+pub struct Args {
+    /// The ID of the task to edit
+    #[arg(short, long)]
+    id: usize,
+    /// Opens the task in $EDITOR, after applying the other options
+    #[arg(short, long)]
+    editor: bool,
+    /// The new name
+    #[arg(long)]
+    name: Option<String>,
+    /// The date the task is due
+    #[arg(long)]
+    date: Option<String>,
+    /// The date the task becomes actionable. Empty to clear
+    #[arg(long)]
+    scheduled: Option<String>,
+    /// How often the task repeats
+    #[arg(long)]
+    repeats: Option<String>,
+    /// A description or url for your task
+    #[arg(long)]
+    description: Option<String>,
+    /// Comma separated tags, replacing the current ones
+    #[arg(long)]
+    tags: Option<String>,
+    /// How long the task should take, e.g. 1h30m
+    #[arg(long)]
+    estimate: Option<String>,
+    /// Comma separated reminders before the due date, e.g. 15m,1d
+    #[arg(long)]
+    reminders: Option<String>,
+    /// The project the task belongs to. Empty to clear
+    #[arg(long)]
+    project: Option<String>,
+    /// How important the task is
+    #[arg(long)]
+    priority: Option<Priority>,
+    /// Comma separated URLs, file paths or mailto: addresses, besides the ones in the description
+    #[arg(long)]
+    links: Option<String>,
+    /// The format to display the edited task with
+    #[arg(long)]
+    format: Option<Format>,
+}
+
+pub fn run(mut app: App, args: Args) -> Result<()> {
+    let task = match app.get_task(args.id) {
+        Some(task) => task.clone(),
+        None => return Err(anyhow!("Task with id {} not found", args.id)),
+    };
+
+    let mut task_form = TaskForm::from_task(&task, &app.settings);
+    let changes = [
+        (&mut task_form.name, args.name),
+        (&mut task_form.date, args.date),
+        (&mut task_form.scheduled, args.scheduled),
+        (&mut task_form.repeats, args.repeats),
+        (&mut task_form.description, args.description),
+        (&mut task_form.tags, args.tags),
+        (&mut task_form.estimate, args.estimate),
+        (&mut task_form.reminders, args.reminders),
+        (&mut task_form.project, args.project),
+        (&mut task_form.priority, args.priority.map(|p| p.to_string())),
+        (&mut task_form.links, args.links),
+    ];
+    let mut changed = false;
+    for (field, value) in changes {
+        if let Some(value) = value {
+            *field = value;
+            changed = true;
+        }
+    }
+
+    let edited = if args.editor {
+        let edited = editor::edit_form(&task_form, |task_form| {
+            task_form.submit_onto(task.clone(), &app.settings)
+        })?;
+        match edited {
+            Some(edited) => edited,
+            None => {
+                println!("Edit cancelled");
+                return Ok(());
+            }
+        }
+    } else if changed {
+        task_form.submit_onto(task, &app.settings)?
+    } else {
+        return Err(anyhow!("Nothing to change. Pass --editor or the fields to change"));
+    };
+
+    let task = app.update_task(edited).unwrap();
+    cli_utils::print_task(&task, args.format, &app.settings);
+
+    Ok(())
+}
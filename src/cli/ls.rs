@@ -1,7 +1,11 @@
 use anyhow::Result;
 use clap::{Parser, ValueEnum};
 use crate::app::App;
+use crate::query::Query;
+use crate::sort::SortOrder;
+use crate::status::Status;
 use crate::task::Task;
+use crate::utils;
 
 use super::cli_utils;
 use super::formats::Format;
@@ -14,9 +18,25 @@ pub struct Args {
     /// Whether to show complete tasks
     #[arg(short,long)]
     show_complete: bool,
+    /// Whether to show tasks scheduled for a later date
+    #[arg(short = 'w', long)]
+    show_waiting: bool,
     /// Filter the tasks to show
     #[arg(long)]
     filter: Option<Filter>,
+    /// Only show tasks with these statuses, e.g. todo,in-progress
+    #[arg(long, value_delimiter = ',')]
+    status: Vec<Status>,
+    /// Only show tasks matching a filter expression, e.g. "due<=+3d and tag:work".
+    /// Complete and waiting tasks are not hidden by default when used, --status still applies
+    #[arg(long = "where")]
+    where_: Option<String>,
+    /// How to sort the tasks, e.g. due,priority,-created,name. Defaults to the sort in the config
+    #[arg(long, allow_hyphen_values = true)]
+    sort: Option<String>,
+    /// A view from the config to list the tasks with. Defaults to the active context
+    #[arg(long)]
+    view: Option<String>,
 }
 
 #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
@@ -29,13 +49,41 @@ enum Filter {
 }
 
 pub fn run(app: App, args: Args) -> Result<()> {
-    let Args { format, show_complete, filter } = args;
+    let Args { format, show_complete, show_waiting, filter, status, where_, sort, view } = args;
+    let query = match where_ {
+        Some(where_) => Some(Query::parse(&where_, &app.settings)?),
+        None => None,
+    };
 
-    let mut tasks_iter: Box<dyn Iterator<Item = &Task>> = if !show_complete {
-        Box::new(app.tasks.iter().filter(|&t| !t.complete))
-    } else {
-        Box::new(app.tasks.iter())
+    let view = match view {
+        Some(view) => Some(app.settings.get_view(&view)?),
+        None => app.settings.get_context(),
     };
+    let view_query = match view {
+        Some(view) => view.get_query(&app.settings)?,
+        None => None,
+    };
+    let show_complete = show_complete || view.and_then(|v| v.show_complete).unwrap_or(false);
+    let show_waiting = show_waiting || view.and_then(|v| v.show_waiting).unwrap_or(false);
+
+    let mut tasks_iter: Box<dyn Iterator<Item = &Task>> = Box::new(app.tasks.iter());
+    if let Some(query) = &query {
+        tasks_iter = Box::new(tasks_iter.filter(|&t| query.matches(t)));
+    }
+
+    if !status.is_empty() {
+        tasks_iter = Box::new(tasks_iter.filter(|&t| status.contains(&t.status)));
+    } else if !show_complete && query.is_none() {
+        tasks_iter = Box::new(tasks_iter.filter(|&t| !t.status.is_closed()));
+    }
+
+    if !show_waiting && query.is_none() {
+        tasks_iter = Box::new(tasks_iter.filter(|&t| !t.is_waiting()));
+    }
+
+    if let Some(view_query) = &view_query {
+        tasks_iter = Box::new(tasks_iter.filter(|&t| view_query.matches(t)));
+    }
 
     let now = chrono::Local::now();
 
@@ -66,8 +114,23 @@ pub fn run(app: App, args: Args) -> Result<()> {
         _ => {}
     }
 
-    let tasks: Vec<&Task> = tasks_iter.collect();
+    let mut tasks: Vec<&Task> = tasks_iter.collect();
+    let sort = match (sort, view) {
+        (Some(sort), _) => Some(SortOrder::parse_from_str(&sort)?),
+        (None, Some(view)) => Some(view.get_sort_order(&app.settings)?),
+        (None, None) => None,
+    };
+    if let Some(sort) = sort {
+        tasks.sort_by(|a, b| sort.compare(a, b));
+    }
+    let effort = utils::total_estimate(tasks.iter().copied());
     cli_utils::print_tasks(tasks, format, &app.settings);
 
+    if filter == Some(Filter::Today) && format != Some(Format::Json) {
+        let (effort, over_capacity) = utils::effort_summary(&effort, &app.settings);
+        let warning = if over_capacity { " (over capacity)" } else { "" };
+        println!("Total effort: {}{}", effort, warning);
+    }
+
     Ok(())
 }
@@ -1,12 +1,24 @@
 use anyhow::Result;
 use clap::Parser;
 use crate::app::App;
+use crate::configuration::IconPreset;
+use crate::ui;
 
 mod ls;
 mod add;
+mod edit;
+mod open;
 mod delete;
-mod complete;
+mod status;
+mod start;
+mod stop;
+mod report;
+mod snooze;
+mod daemon;
+mod search;
+mod context;
 mod cli_utils;
+mod selection;
 
 // Shared enums and structs
 mod formats;
@@ -15,28 +27,64 @@ mod formats;
 #[command(author, version, about, long_about = None)]
 struct Args {
     #[command(subcommand)]
-    command: Command,
+    command: Option<Command>,
+    /// The icons to use this time, e.g. ascii for logs
+    #[arg(long, global = true, value_enum)]
+    icons: Option<IconPreset>,
 }
 
 #[derive(Parser)]
 enum Command {
     /// Lists all the tasks
     Ls(ls::Args),
+    /// Searches task names and descriptions
+    Search(search::Args),
     /// Adds a task to your todos
     Add(add::Args),
-    /// Deletes a task from your todos
+    /// Edits a task, or opens it in your editor
+    Edit(edit::Args),
+    /// Opens a link of a task
+    Open(open::Args),
+    /// Deletes tasks from your todos
     Delete(delete::Args),
-    /// Marks a task as complete or incomplete
-    Complete(complete::Args),
+    /// Changes the status of tasks
+    Status(status::Args),
+    /// Starts tracking time on a task
+    Start(start::Args),
+    /// Stops the running timer
+    Stop(stop::Args),
+    /// Postpones a task, or every overdue task
+    Snooze(snooze::Args),
+    /// Shows reports about your tasks
+    Report(report::Args),
+    /// Watches your tasks and sends their reminders
+    Daemon(daemon::Args),
+    /// Shows or switches the active view
+    Context(context::Args),
 }
 
-pub fn start_cli(app: App) -> Result<()> {
+/// Runs a command, or the TUI when there's none
+pub fn start_cli(mut app: App) -> Result<()> {
     let args = Args::parse();
+    app.settings.icons.forced = args.icons;
 
-    match args.command {
+    let command = match args.command {
+        Some(command) => command,
+        None => return ui::start_ui(app),
+    };
+    match command {
         Command::Ls(args) => ls::run(app, args),
+        Command::Search(args) => search::run(app, args),
         Command::Add(args) => add::run(app, args),
+        Command::Edit(args) => edit::run(app, args),
+        Command::Open(args) => open::run(app, args),
         Command::Delete(args) => delete::run(app, args),
-        Command::Complete(args) => complete::run(app, args),
+        Command::Status(args) => status::run(app, args),
+        Command::Start(args) => start::run(app, args),
+        Command::Stop(args) => stop::run(app, args),
+        Command::Snooze(args) => snooze::run(app, args),
+        Command::Report(args) => report::run(app, args),
+        Command::Daemon(args) => daemon::run(app, args),
+        Command::Context(args) => context::run(app, args),
     }
 }
@@ -0,0 +1,52 @@
+use anyhow::{anyhow, Result};
+use clap::Parser;
+use std::io::Write;
+
+use crate::app::App;
+use crate::utils;
+
+#[derive(Parser)]
+pub struct Args {
+    /// The ID of the task whose link to open
+    #[arg(short, long)]
+    id: usize,
+    /// Which link to open, starting at 1. Asks when the task has several
+    #[arg(short = 'n', long)]
+    index: Option<usize>,
+}
+
+pub fn run(app: App, args: Args) -> Result<()> {
+    let task = match app.get_task(args.id) {
+        Some(task) => task,
+        None => return Err(anyhow!("Task with id {} not found", args.id)),
+    };
+    let links = task.all_links();
+    if links.is_empty() {
+        return Err(anyhow!("Task {} has no links", args.id));
+    }
+
+    let index = match args.index {
+        Some(index) => index,
+        None if links.len() == 1 => 1,
+        None => {
+            for (idx, link) in links.iter().enumerate() {
+                println!("{}: {}", idx + 1, link);
+            }
+            print!("Open which link? [1-{}] ", links.len());
+            std::io::stdout().flush()?;
+            let mut answer = String::new();
+            std::io::stdin().read_line(&mut answer)?;
+            match answer.trim() {
+                "" => return Ok(()),
+                answer => answer.parse().map_err(|_| anyhow!("Invalid link number '{}'", answer))?,
+            }
+        }
+    };
+
+    let link = match index.checked_sub(1).and_then(|idx| links.get(idx)) {
+        Some(link) => link,
+        None => return Err(anyhow!("Task {} has {} link(s), not {}", args.id, links.len(), index)),
+    };
+    println!("Opening {}", link);
+    utils::open_link(link)
+}
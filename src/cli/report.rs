@@ -0,0 +1,169 @@
+use super::formats::Format;
+use crate::app::App;
+use crate::task::Task;
+use crate::time_entry::TimeEntry;
+use crate::utils;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Days, Duration, Local, NaiveDate};
+use clap::{Parser, Subcommand, ValueEnum};
+use std::collections::BTreeMap;
+
+#[derive(Parser)]
+pub struct Args {
+    #[command(subcommand)]
+    report: Report,
+}
+
+#[derive(Subcommand)]
+enum Report {
+    /// Shows the time tracked on your tasks
+    Time(TimeArgs),
+}
+
+#[derive(Parser)]
+struct TimeArgs {
+    /// Only count time tracked after this date
+    #[arg(long)]
+    since: Option<String>,
+    /// How to group the tracked time
+    #[arg(long, default_value = "task")]
+    by: GroupBy,
+    /// The format to print the report with
+    #[arg(short, long)]
+    format: Option<Format>,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
+enum GroupBy {
+    Task,
+    Tag,
+    Day,
+}
+
+pub fn run(app: App, args: Args) -> Result<()> {
+    match args.report {
+        Report::Time(args) => time_report(app, args),
+    }
+}
+
+fn time_report(app: App, args: TimeArgs) -> Result<()> {
+    let TimeArgs { since, by, format } = args;
+    let since = match since {
+        Some(since) => Some(parse_since(&since, &app)?),
+        None => None,
+    };
+
+    let totals = match by {
+        GroupBy::Task => totals_by_task(&app.tasks, since),
+        GroupBy::Tag => totals_by_tag(&app.tasks, since),
+        GroupBy::Day => totals_by_day(&app.tasks, since, &app),
+    };
+
+    match format {
+        Some(Format::Json) => {
+            let totals: Vec<serde_json::Value> = totals
+                .iter()
+                .map(|(key, total)| serde_json::json!({ "key": key, "seconds": total.num_seconds() }))
+                .collect();
+            println!("{}", serde_json::to_string_pretty(&totals).unwrap());
+        }
+        _ => {
+            let mut sum = Duration::zero();
+            for (key, total) in totals.iter() {
+                let key = utils::ellipsis_or_pad(key, 30);
+                println!("{}\t{}", key, utils::format_duration(total));
+                sum = sum + *total;
+            }
+            // Tasks with several tags are counted once per tag
+            if by != GroupBy::Tag {
+                let key = utils::ellipsis_or_pad(&"Total".to_string(), 30);
+                println!("{}\t{}", key, utils::format_duration(&sum));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Dates without a time count from the start of that day
+fn parse_since(since: &String, app: &App) -> Result<DateTime<Local>> {
+    let date = utils::parse_date(since, &app.settings).context("Invalid since date")?;
+    if utils::date_has_time(&date) {
+        Ok(date)
+    } else {
+        Ok(utils::start_of_day(date.date_naive()))
+    }
+}
+
+fn entry_time(entry: &TimeEntry, since: Option<DateTime<Local>>) -> Duration {
+    let since = since.unwrap_or(entry.start);
+    entry.duration_between(since, Local::now())
+}
+
+fn task_time(task: &Task, since: Option<DateTime<Local>>) -> Duration {
+    task.time_entries
+        .iter()
+        .fold(Duration::zero(), |acc, e| acc + entry_time(e, since))
+}
+
+fn totals_by_task(tasks: &[Task], since: Option<DateTime<Local>>) -> Vec<(String, Duration)> {
+    tasks
+        .iter()
+        .map(|t| (format!("{} ({})", t.name, t.get_id()), task_time(t, since)))
+        .filter(|(_, total)| *total > Duration::zero())
+        .collect()
+}
+
+fn totals_by_tag(tasks: &[Task], since: Option<DateTime<Local>>) -> Vec<(String, Duration)> {
+    let mut totals: BTreeMap<String, Duration> = BTreeMap::new();
+    for task in tasks {
+        let time = task_time(task, since);
+        if time == Duration::zero() {
+            continue;
+        }
+
+        let tags = if task.tags.is_empty() {
+            vec!["untagged".to_string()]
+        } else {
+            task.tags.clone()
+        };
+        for tag in tags {
+            let total = totals.entry(tag).or_insert_with(Duration::zero);
+            *total = *total + time;
+        }
+    }
+    totals.into_iter().collect()
+}
+
+fn totals_by_day(
+    tasks: &[Task],
+    since: Option<DateTime<Local>>,
+    app: &App,
+) -> Vec<(String, Duration)> {
+    let mut totals: BTreeMap<NaiveDate, Duration> = BTreeMap::new();
+    for entry in tasks.iter().flat_map(|t| t.time_entries.iter()) {
+        // Split entries that cross midnight between both days
+        let mut day = entry.start.date_naive();
+        while day <= entry.end_or_now().date_naive() {
+            let next_day = day.checked_add_days(Days::new(1)).unwrap();
+            let from = match since {
+                Some(since) => since.max(utils::start_of_day(day)),
+                None => utils::start_of_day(day),
+            };
+            let time = entry.duration_between(from, utils::start_of_day(next_day));
+            if time > Duration::zero() {
+                let total = totals.entry(day).or_insert_with(Duration::zero);
+                *total = *total + time;
+            }
+            day = next_day;
+        }
+    }
+
+    totals
+        .into_iter()
+        .map(|(day, total)| {
+            let format = app.settings.date_formats.display_date_format.as_str();
+            (day.format(format).to_string(), total)
+        })
+        .collect()
+}
@@ -0,0 +1,28 @@
+use super::{cli_utils, formats::Format};
+use crate::app::App;
+use crate::search;
+use anyhow::Result;
+use clap::Parser;
+
+#[derive(Parser)]
+pub struct Args {
+    /// The text to look for in task names and descriptions
+    query: String,
+    /// The format to print the matching tasks with
+    #[arg(short, long)]
+    format: Option<Format>,
+}
+
+pub fn run(app: App, args: Args) -> Result<()> {
+    let Args { query, format } = args;
+    let tasks = search::search(&query, &app.tasks);
+
+    if tasks.is_empty() {
+        println!("No tasks match {}", query);
+        return Ok(());
+    }
+
+    cli_utils::print_tasks(tasks, format, &app.settings);
+
+    Ok(())
+}
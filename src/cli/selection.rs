@@ -0,0 +1,105 @@
+use super::cli_utils;
+use crate::app::App;
+use crate::query::Query;
+use anyhow::{anyhow, Result};
+use std::io::Write;
+
+/// Picks the tasks a bulk command changes
+#[derive(clap::Args)]
+pub struct Selection {
+    /// The IDs of the tasks, e.g. 3 or 3,5,8-10
+    #[arg(short, long = "id", value_delimiter = ',')]
+    ids: Vec<String>,
+    /// Pick every task matching a filter expression instead
+    #[arg(long = "where", conflicts_with = "ids")]
+    where_: Option<String>,
+    /// Print the tasks that would change without changing them
+    #[arg(short = 'n', long)]
+    dry_run: bool,
+    /// Don't ask for confirmation when changing many tasks
+    #[arg(short, long)]
+    yes: bool,
+}
+
+impl Selection {
+    /// Whether any tasks were picked, for commands with other ways to pick them
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty() && self.where_.is_none()
+    }
+
+    /// The IDs of the picked tasks. Missing single IDs are an error, ranges only pick existing tasks
+    pub fn resolve(&self, app: &App) -> Result<Vec<usize>> {
+        if let Some(where_) = &self.where_ {
+            let query = Query::parse(where_, &app.settings)?;
+            let ids = app
+                .tasks
+                .iter()
+                .filter(|t| query.matches(t))
+                .map(|t| t.get_id())
+                .collect();
+            return Ok(ids);
+        }
+
+        if self.ids.is_empty() {
+            return Err(anyhow!("One of --id or --where is required"));
+        }
+
+        let mut ids = vec![];
+        for id in self.ids.iter() {
+            let id = id.trim();
+            let range = match id.split_once('-') {
+                Some((start, end)) => {
+                    let start: usize = start.trim().parse()?;
+                    let end: usize = end.trim().parse()?;
+                    if start > end {
+                        return Err(anyhow!("Invalid range '{}'", id));
+                    }
+                    (start..=end).filter(|&i| app.get_task(i).is_some()).collect()
+                }
+                None => {
+                    let id: usize = id.parse().map_err(|_| anyhow!("Invalid id '{}'", id))?;
+                    if app.get_task(id).is_none() {
+                        return Err(anyhow!("Task with id {} not found", id));
+                    }
+                    vec![id]
+                }
+            };
+            for id in range {
+                if !ids.contains(&id) {
+                    ids.push(id);
+                }
+            }
+        }
+        Ok(ids)
+    }
+
+    /// Whether to go ahead with the change. Dry runs print the tasks and stop here,
+    /// and changes to more tasks than `confirm_over` in the config ask first
+    pub fn confirm(&self, action: &str, ids: &[usize], app: &App) -> Result<bool> {
+        let tasks = ids.iter().filter_map(|&id| app.get_task(id)).collect();
+        if self.dry_run {
+            println!("Would {} {} task(s):", action, ids.len());
+            cli_utils::print_tasks(tasks, None, &app.settings);
+            return Ok(false);
+        }
+
+        if self.yes || ids.len() <= app.settings.confirm_over {
+            return Ok(true);
+        }
+
+        cli_utils::print_tasks(tasks, None, &app.settings);
+        print!("{} {} tasks? [y/N] ", capitalize(action), ids.len());
+        std::io::stdout().flush()?;
+        let mut answer = String::new();
+        std::io::stdin().read_line(&mut answer)?;
+        Ok(matches!(answer.trim().to_lowercase().as_str(), "y" | "yes"))
+    }
+}
+
+fn capitalize(s: &str) -> String {
+    let mut chars = s.chars();
+    match chars.next() {
+        Some(c) => c.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
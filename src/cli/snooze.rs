@@ -0,0 +1,53 @@
+use super::{cli_utils, formats::Format, selection::Selection};
+use crate::app::App;
+use anyhow::{anyhow, Result};
+use clap::Parser;
+
+#[derive(Parser)]
+pub struct Args {
+    /// When to move the tasks to: today, tomorrow, mon, next-fri, 2d, -1d, 1w or a date
+    #[arg(allow_hyphen_values = true)]
+    when: Option<String>,
+    #[command(flatten)]
+    selection: Selection,
+    /// Snooze every overdue task instead. Moves them to today by default
+    #[arg(long, conflicts_with_all = ["ids", "where_"])]
+    overdue: bool,
+    /// The format to print the snoozed tasks with
+    #[arg(short, long)]
+    format: Option<Format>,
+}
+
+pub fn run(mut app: App, args: Args) -> Result<()> {
+    let Args {
+        when,
+        selection,
+        overdue,
+        format,
+    } = args;
+
+    let (ids, when) = if overdue {
+        (app.overdue_task_ids(), when.unwrap_or("today".to_string()))
+    } else if selection.is_empty() {
+        return Err(anyhow!("One of --id, --overdue or --where is required"));
+    } else {
+        let ids = selection.resolve(&app)?;
+        (ids, when.ok_or(anyhow!("Missing when to snooze the tasks to"))?)
+    };
+
+    if ids.is_empty() {
+        match overdue {
+            true => println!("No overdue tasks"),
+            false => println!("No tasks to snooze"),
+        }
+        return Ok(());
+    }
+    if !selection.confirm("snooze", &ids, &app)? {
+        return Ok(());
+    }
+
+    let tasks = app.snooze_tasks(&ids, &when)?;
+    cli_utils::print_tasks(tasks.iter().collect(), format, &app.settings);
+
+    Ok(())
+}
@@ -0,0 +1,25 @@
+use super::{cli_utils, formats::Format};
+use crate::app::App;
+use anyhow::{anyhow, Result};
+use clap::Parser;
+
+#[derive(Parser)]
+pub struct Args {
+    /// The ID of the task to track time on
+    #[arg(short, long)]
+    id: usize,
+    /// The format to print the task with
+    #[arg(short, long)]
+    format: Option<Format>,
+}
+
+pub fn run(mut app: App, args: Args) -> Result<()> {
+    let Args { id, format } = args;
+    let task = match app.start_timer(id) {
+        Some(task) => task,
+        None => return Err(anyhow!("Task with id {} not found", id)),
+    };
+    cli_utils::print_task(&task, format, &app.settings);
+
+    Ok(())
+}
@@ -0,0 +1,39 @@
+use super::{cli_utils, formats::Format, selection::Selection};
+use crate::app::App;
+use crate::status::Status;
+use anyhow::Result;
+use clap::Parser;
+
+#[derive(Parser)]
+pub struct Args {
+    #[command(flatten)]
+    selection: Selection,
+    /// The new status of the tasks
+    #[arg(short, long)]
+    status: Status,
+    /// The format to print the updated tasks with
+    #[arg(short, long)]
+    format: Option<Format>,
+}
+
+pub fn run(mut app: App, args: Args) -> Result<()> {
+    let Args { selection, status, format } = args;
+    let ids = selection.resolve(&app)?;
+
+    if ids.is_empty() {
+        println!("No tasks to update");
+        return Ok(());
+    }
+    let action = format!("mark as {}", status.to_string().to_lowercase());
+    if !selection.confirm(&action, &ids, &app)? {
+        return Ok(());
+    }
+
+    let tasks = app.set_statuses(&ids, status);
+    match tasks.as_slice() {
+        [task] => cli_utils::print_task(task, format, &app.settings),
+        _ => cli_utils::print_tasks(tasks.iter().collect(), format, &app.settings),
+    }
+
+    Ok(())
+}
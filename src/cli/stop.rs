@@ -0,0 +1,25 @@
+use super::{cli_utils, formats::Format};
+use crate::app::App;
+use anyhow::Result;
+use clap::Parser;
+
+#[derive(Parser)]
+pub struct Args {
+    /// The format to print the stopped tasks with
+    #[arg(short, long)]
+    format: Option<Format>,
+}
+
+pub fn run(mut app: App, args: Args) -> Result<()> {
+    let Args { format } = args;
+    let tasks = app.stop_timers();
+
+    if tasks.is_empty() {
+        println!("No timer is running");
+        return Ok(());
+    }
+
+    cli_utils::print_tasks(tasks.iter().collect(), format, &app.settings);
+
+    Ok(())
+}
@@ -1,5 +1,14 @@
+use crate::keybindings::Keybindings;
+use crate::sort::SortOrder;
+use crate::status::Status;
+use crate::task::Task;
+use crate::theme::ThemeConfig;
 use crate::utils;
+use crate::view::View;
+use anyhow::{anyhow, Result};
+use chrono::Duration;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs;
 use std::fs::OpenOptions;
 use std::io::prelude::*;
@@ -9,35 +18,198 @@ pub struct Settings {
     pub db_file: String,
     pub date_formats: DateFormats,
     pub show_complete: bool,
+    #[serde(default)]
+    pub show_waiting: bool,
+    #[serde(default)]
     pub icons: Icons,
+    /// How much estimated work fits in a day, e.g. `8h`. Empty to disable
+    #[serde(default = "Settings::default_daily_capacity")]
+    pub daily_capacity: String,
+    /// Open tasks due within this window are highlighted, e.g. `1d`. Empty to disable
+    #[serde(default = "Settings::default_due_soon")]
+    pub due_soon: String,
+    #[serde(default = "Notifier::default")]
+    pub notifier: Notifier,
+    /// The default sort order, e.g. `due,priority,-created,name`
+    #[serde(default = "Settings::default_sort")]
+    pub sort: String,
+    /// Named views, e.g. `"work": {"filter": "tag:work", "tags": ["work"]}`
+    #[serde(default)]
+    pub views: BTreeMap<String, View>,
+    /// The active view. It applies to `ls` and the TUI, and to new tasks
+    #[serde(default)]
+    pub context: Option<String>,
+    /// Bulk commands changing more tasks than this ask for confirmation
+    #[serde(default = "Settings::default_confirm_over")]
+    pub confirm_over: usize,
+    #[serde(default)]
+    pub keybindings: Keybindings,
+    /// Lets the TUI take clicks and scrolling. Off keeps the terminal's own text selection
+    #[serde(default = "Settings::default_mouse")]
+    pub mouse: bool,
+    #[serde(default)]
+    pub theme: ThemeConfig,
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+/// The bundled icon sets. `auto` picks `unicode`, or `ascii` on terminals without UTF-8
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum IconPreset {
+    #[default]
+    Auto,
+    NerdFont,
+    Unicode,
+    Ascii,
+}
+
+struct IconSet {
+    complete: &'static str,
+    incomplete: &'static str,
+    in_progress: &'static str,
+    blocked: &'static str,
+    cancelled: &'static str,
+    repeats: &'static str,
+    timer: &'static str,
+}
+
+impl IconPreset {
+    /// Resolves `auto` from the terminal and locale
+    pub fn detect(self) -> IconPreset {
+        if self != IconPreset::Auto {
+            return self;
+        }
+
+        if std::env::var("TERM").is_ok_and(|term| term == "dumb") {
+            return IconPreset::Ascii;
+        }
+        let locale = ["LC_ALL", "LC_CTYPE", "LANG"]
+            .iter()
+            .filter_map(|var| std::env::var(var).ok())
+            .find(|value| !value.is_empty());
+        match locale {
+            Some(locale) => {
+                let locale = locale.to_lowercase();
+                if locale.contains("utf-8") || locale.contains("utf8") {
+                    IconPreset::Unicode
+                } else {
+                    IconPreset::Ascii
+                }
+            }
+            // Windows terminals don't set a locale but handle UTF-8
+            None if cfg!(windows) => IconPreset::Unicode,
+            None => IconPreset::Ascii,
+        }
+    }
+
+    fn icon_set(self) -> IconSet {
+        match self.detect() {
+            IconPreset::NerdFont => IconSet {
+                complete: "󰄴",
+                incomplete: "󰝦",
+                in_progress: "󰦖",
+                blocked: "󰏦",
+                cancelled: "󰅚",
+                repeats: "",
+                timer: "󰔛",
+            },
+            IconPreset::Unicode | IconPreset::Auto => IconSet {
+                complete: "✓",
+                incomplete: "○",
+                in_progress: "◐",
+                blocked: "⊘",
+                cancelled: "✗",
+                repeats: "↻",
+                timer: "◷",
+            },
+            IconPreset::Ascii => IconSet {
+                complete: "[x]",
+                incomplete: "[ ]",
+                in_progress: "[~]",
+                blocked: "[!]",
+                cancelled: "[-]",
+                repeats: "(r)",
+                timer: "(t)",
+            },
+        }
+    }
+}
+
+/// Icons from a preset. Icons set here win over the preset, unless the preset
+/// comes from `--icons`. Icons equal to the Nerd Font ones are ignored: older
+/// versions saved those as the defaults, and they'd hide the preset
+#[derive(Deserialize, Serialize, Debug, Default)]
 pub struct Icons {
-    pub complete: String,
-    pub incomplete: String,
-    pub repeats: String,
+    #[serde(default)]
+    pub preset: IconPreset,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub complete: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub incomplete: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub in_progress: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub blocked: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cancelled: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub repeats: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timer: Option<String>,
+    /// The preset picked with `--icons`, never saved
+    #[serde(skip)]
+    pub forced: Option<IconPreset>,
 }
 
 impl Icons {
-    pub fn default() -> Self {
-        Icons {
-            complete: "󰄴".to_string(),
-            incomplete: "󰝦".to_string(),
-            repeats: "".to_string(),
+    fn pick(&self, configured: &Option<String>, slot: fn(&IconSet) -> &'static str) -> String {
+        let nerd_font = slot(&IconPreset::NerdFont.icon_set());
+        match (self.forced, configured) {
+            (None, Some(icon)) if icon != nerd_font => icon.clone(),
+            _ => slot(&self.icon_set()).to_string(),
         }
     }
 
-    pub fn get_complete_icon(&self, complete: bool) -> String {
-        let icon = if complete {
-            self.complete.clone()
-        } else {
-            self.incomplete.clone()
+    fn icon_set(&self) -> IconSet {
+        self.forced.unwrap_or(self.preset).icon_set()
+    }
+
+    pub fn get_status_icon(&self, status: Status) -> String {
+        let icon = match status {
+            Status::Todo => self.pick(&self.incomplete, |set| set.incomplete),
+            Status::InProgress => self.pick(&self.in_progress, |set| set.in_progress),
+            Status::Blocked => self.pick(&self.blocked, |set| set.blocked),
+            Status::Complete => self.pick(&self.complete, |set| set.complete),
+            Status::Cancelled => self.pick(&self.cancelled, |set| set.cancelled),
         };
 
         // Needs some padding
         format!(" {}", icon)
     }
+
+    pub fn repeats(&self) -> String {
+        self.pick(&self.repeats, |set| set.repeats)
+    }
+
+    pub fn timer(&self) -> String {
+        self.pick(&self.timer, |set| set.timer)
+    }
+}
+
+/// The command `todo-rs daemon` runs to deliver reminders. `{name}`, `{date}`,
+/// `{id}` and `{description}` in the arguments are replaced with the task's values
+#[derive(Deserialize, Serialize, Debug)]
+pub struct Notifier {
+    pub command: String,
+    pub args: Vec<String>,
+}
+
+impl Default for Notifier {
+    fn default() -> Self {
+        Notifier {
+            command: "notify-send".to_string(),
+            args: vec!["todo-rs".to_string(), "{name} is due {date}".to_string()],
+        }
+    }
 }
 
 #[derive(Deserialize, Serialize, Debug)]
@@ -65,14 +237,83 @@ impl DateFormats {
 
 impl Settings {
     pub fn default() -> Self {
+        Self::with_db_file(Self::get_default_db_file())
+    }
+
+    /// The default settings with tasks stored in `db_file`. Unlike `default`, this
+    /// doesn't touch the config directory
+    pub fn with_db_file(db_file: String) -> Self {
         Settings {
-            db_file: Self::get_default_db_file(),
+            db_file,
             show_complete: true,
+            show_waiting: false,
             icons: Icons::default(),
             date_formats: DateFormats::new(),
+            daily_capacity: Self::default_daily_capacity(),
+            due_soon: Self::default_due_soon(),
+            notifier: Notifier::default(),
+            sort: Self::default_sort(),
+            views: BTreeMap::new(),
+            context: None,
+            confirm_over: Self::default_confirm_over(),
+            keybindings: Keybindings::default(),
+            mouse: Self::default_mouse(),
+            theme: ThemeConfig::default(),
         }
     }
 
+    fn default_daily_capacity() -> String {
+        "8h".to_string()
+    }
+
+    fn default_due_soon() -> String {
+        "1d".to_string()
+    }
+
+    fn default_mouse() -> bool {
+        true
+    }
+
+    fn default_sort() -> String {
+        "due".to_string()
+    }
+
+    fn default_confirm_over() -> usize {
+        5
+    }
+
+    /// The sort order of the active context, or the configured one.
+    /// Falls back to sorting by due date if the order is invalid
+    pub fn get_sort_order(&self) -> SortOrder {
+        match self.get_context() {
+            Some(view) => view.get_sort_order(self).unwrap_or_default(),
+            None => SortOrder::parse_from_str(&self.sort).unwrap_or_default(),
+        }
+    }
+
+    pub fn get_view(&self, name: &str) -> Result<&View> {
+        self.views.get(name).ok_or_else(|| {
+            let names: Vec<&str> = self.views.keys().map(|k| k.as_str()).collect();
+            anyhow!("Unknown view '{}'. Views: {}", name, names.join(", "))
+        })
+    }
+
+    pub fn get_context(&self) -> Option<&View> {
+        self.context.as_ref().and_then(|name| self.views.get(name))
+    }
+
+    pub fn get_daily_capacity(&self) -> Option<Duration> {
+        utils::parse_duration(&self.daily_capacity).ok()
+    }
+
+    pub fn get_due_soon(&self) -> Option<Duration> {
+        utils::parse_duration(&self.due_soon).ok()
+    }
+
+    pub fn is_due_soon(&self, task: &Task) -> bool {
+        self.get_due_soon().is_some_and(|window| task.is_due_soon(window))
+    }
+
     pub fn default_path() -> String {
         let home = std::env::var("HOME").unwrap();
         let path = format!("{}/.config/todo-rs", home);
@@ -96,6 +337,11 @@ impl Settings {
         path
     }
 
+    pub fn get_reminders_file() -> String {
+        let path = Self::default_path();
+        format!("{}/reminders.json", path)
+    }
+
     pub fn get_settings_path() -> String {
         let path = Self::default_path();
         let path = format!("{}/settings.json", path);
@@ -116,6 +362,29 @@ impl Settings {
         self.show_complete = show_complete;
         self.save_state();
     }
+
+    /// Sets the sort order of the active context, or the default one
+    pub fn set_sort(&mut self, sort: &SortOrder) {
+        let context = self.context.clone().and_then(|name| self.views.get_mut(&name));
+        match context {
+            Some(view) => {
+                view.sort = Some(sort.to_string());
+                view.group = None;
+            }
+            None => self.sort = sort.to_string(),
+        }
+        self.save_state();
+    }
+
+    pub fn set_context(&mut self, context: Option<String>) {
+        self.context = context;
+        self.save_state();
+    }
+
+    pub fn set_show_waiting(&mut self, show_waiting: bool) {
+        self.show_waiting = show_waiting;
+        self.save_state();
+    }
 }
 
 pub fn get_configuration() -> Settings {
@@ -0,0 +1,103 @@
+use crate::task_form::TaskForm;
+use anyhow::{anyhow, Context, Result};
+use std::fs::{self, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::path::PathBuf;
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The header of task buffers. Its comment lines are dropped when reading them back
+const TASK_HEADER: &str = "# Edit the task and close the editor to save it.
+# Empty the file to cancel.
+";
+
+/// `$VISUAL` or `$EDITOR`, falling back to vi or notepad
+fn editor_command() -> String {
+    ["VISUAL", "EDITOR"]
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find(|editor| !editor.trim().is_empty())
+        .unwrap_or_else(|| if cfg!(windows) { "notepad" } else { "vi" }.to_string())
+}
+
+/// Creates a new file only the user can read in the temp dir. It's never an existing
+/// file, so a symlink planted there can't redirect the write
+fn create_temp_file(text: &str, extension: &str) -> Result<PathBuf> {
+    let mut options = OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+
+    let pid = std::process::id();
+    for attempt in 0..100 {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.subsec_nanos());
+        let name = format!("todo-rs-{}-{}-{}.{}", pid, nanos, attempt, extension);
+        let path = std::env::temp_dir().join(name);
+        match options.open(&path) {
+            Ok(mut file) => {
+                file.write_all(text.as_bytes())?;
+                return Ok(path);
+            }
+            Err(e) if e.kind() == ErrorKind::AlreadyExists => continue,
+            Err(e) => return Err(e).context("Could not create a temporary file"),
+        }
+    }
+    Err(anyhow!("Could not create a temporary file"))
+}
+
+/// Opens text in the editor and returns it once the editor exits
+pub fn edit_text(text: &str, extension: &str) -> Result<String> {
+    let path = create_temp_file(text, extension)?;
+
+    // The editor can come with arguments, e.g. `code --wait`
+    let editor = editor_command();
+    let mut parts = editor.split_whitespace();
+    let program = parts.next().unwrap_or("vi");
+    let status = Command::new(program)
+        .args(parts)
+        .arg(&path)
+        .status()
+        .with_context(|| format!("Could not start the editor '{}'", editor));
+
+    let edited = fs::read_to_string(&path);
+    fs::remove_file(&path).ok();
+    if !status?.success() {
+        return Err(anyhow!("The editor '{}' exited with an error", editor));
+    }
+    Ok(edited?)
+}
+
+/// Edits a form as TOML until `submit` accepts it. Errors are shown at the top
+/// of the buffer and the editor opens again. `None` if the buffer was emptied
+pub fn edit_form<T>(
+    task_form: &TaskForm,
+    mut submit: impl FnMut(&mut TaskForm) -> Result<T>,
+) -> Result<Option<T>> {
+    let mut buffer = toml::to_string_pretty(task_form)?;
+    let mut error: Option<String> = None;
+    loop {
+        let header = match &error {
+            Some(error) => format!("{}# Error: {}\n", TASK_HEADER, error.replace('\n', "\n# ")),
+            None => TASK_HEADER.to_string(),
+        };
+        let edited = edit_text(&format!("{}{}", header, buffer), "toml")?;
+        buffer = edited
+            .lines()
+            .skip_while(|line| line.starts_with('#'))
+            .collect::<Vec<_>>()
+            .join("\n");
+        if buffer.trim().is_empty() {
+            return Ok(None);
+        }
+
+        let result = toml::from_str::<TaskForm>(&buffer)
+            .map_err(|e| anyhow!(e))
+            .and_then(|mut task_form| submit(&mut task_form));
+        match result {
+            Ok(value) => return Ok(Some(value)),
+            Err(e) => error = Some(e.to_string()),
+        }
+    }
+}
@@ -0,0 +1,469 @@
+use anyhow::{anyhow, Result};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+
+/// Keys for the TUI actions of each page, e.g. `"all_tasks": {"down": ["j", "ctrl-n"]}`.
+/// Actions left out keep their default keys
+#[derive(Deserialize, Serialize, Debug, Default)]
+pub struct Keybindings {
+    #[serde(default)]
+    pub all_tasks: BTreeMap<String, Vec<String>>,
+    #[serde(default)]
+    pub new_task: BTreeMap<String, Vec<String>>,
+}
+
+/// A key with its modifiers, written like `j`, `G`, `ctrl-d`, `alt-enter`, `shift-up` or `pagedown`
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Key {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl Key {
+    pub fn parse_from_str(s: &str) -> Result<Key> {
+        let mut modifiers = KeyModifiers::NONE;
+        let mut rest = s.trim();
+        // A lone `-` is a key, not a separator
+        while let Some((modifier, key)) = rest.split_once('-').filter(|(_, key)| !key.is_empty()) {
+            modifiers |= match modifier.to_lowercase().as_str() {
+                "ctrl" | "c" => KeyModifiers::CONTROL,
+                "alt" | "a" => KeyModifiers::ALT,
+                "shift" | "s" => KeyModifiers::SHIFT,
+                _ => return Err(anyhow!("Unknown modifier '{}' in key '{}'", modifier, s)),
+            };
+            rest = key;
+        }
+
+        let code = match rest.to_lowercase().as_str() {
+            "enter" | "return" => KeyCode::Enter,
+            "esc" | "escape" => KeyCode::Esc,
+            "tab" => KeyCode::Tab,
+            "backtab" => KeyCode::BackTab,
+            "backspace" => KeyCode::Backspace,
+            "delete" | "del" => KeyCode::Delete,
+            "insert" => KeyCode::Insert,
+            "space" => KeyCode::Char(' '),
+            "up" => KeyCode::Up,
+            "down" => KeyCode::Down,
+            "left" => KeyCode::Left,
+            "right" => KeyCode::Right,
+            "home" => KeyCode::Home,
+            "end" => KeyCode::End,
+            "pageup" => KeyCode::PageUp,
+            "pagedown" => KeyCode::PageDown,
+            key if key.len() > 1 && key.starts_with('f') => match key[1..].parse() {
+                Ok(n) if (1..=12).contains(&n) => KeyCode::F(n),
+                _ => return Err(anyhow!("Unknown key '{}'", s)),
+            },
+            _ => {
+                let mut chars = rest.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => KeyCode::Char(c),
+                    _ => return Err(anyhow!("Unknown key '{}'", s)),
+                }
+            }
+        };
+        Ok(Key::normalize(code, modifiers))
+    }
+
+    /// Shift is part of the character itself, e.g. `shift-g` is `G`
+    fn normalize(code: KeyCode, modifiers: KeyModifiers) -> Key {
+        let shift = modifiers.contains(KeyModifiers::SHIFT);
+        let code = match code {
+            KeyCode::Char(c) if shift => KeyCode::Char(c.to_ascii_uppercase()),
+            KeyCode::Tab if shift => KeyCode::BackTab,
+            code => code,
+        };
+        let modifiers = match code {
+            KeyCode::Char(_) | KeyCode::BackTab => modifiers - KeyModifiers::SHIFT,
+            _ => modifiers,
+        };
+        Key { code, modifiers }
+    }
+
+    pub fn from_event(event: &KeyEvent) -> Key {
+        Key::normalize(event.code, event.modifiers)
+    }
+}
+
+impl Display for Key {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            write!(f, "ctrl-")?;
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            write!(f, "alt-")?;
+        }
+        if self.modifiers.contains(KeyModifiers::SHIFT) {
+            write!(f, "shift-")?;
+        }
+        match self.code {
+            KeyCode::Char(' ') => write!(f, "space"),
+            KeyCode::Char(c) => write!(f, "{}", c),
+            KeyCode::F(n) => write!(f, "f{}", n),
+            KeyCode::Enter => write!(f, "enter"),
+            KeyCode::Esc => write!(f, "esc"),
+            KeyCode::Tab => write!(f, "tab"),
+            KeyCode::BackTab => write!(f, "shift-tab"),
+            KeyCode::Backspace => write!(f, "backspace"),
+            KeyCode::Delete => write!(f, "delete"),
+            KeyCode::Insert => write!(f, "insert"),
+            KeyCode::Up => write!(f, "up"),
+            KeyCode::Down => write!(f, "down"),
+            KeyCode::Left => write!(f, "left"),
+            KeyCode::Right => write!(f, "right"),
+            KeyCode::Home => write!(f, "home"),
+            KeyCode::End => write!(f, "end"),
+            KeyCode::PageUp => write!(f, "pageup"),
+            KeyCode::PageDown => write!(f, "pagedown"),
+            code => write!(f, "{:?}", code),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Action {
+    Quit,
+    Down,
+    Up,
+    PageDown,
+    PageUp,
+    First,
+    Last,
+    Complete,
+    CycleStatus,
+    Timer,
+    ShowComplete,
+    ShowWaiting,
+    Delete,
+    SnoozeDay,
+    UnsnoozeDay,
+    Snooze,
+    SnoozeOverdue,
+    Sort,
+    Filter,
+    OpenLink,
+    Search,
+    NextMatch,
+    PrevMatch,
+    Visual,
+    Mark,
+    Tags,
+    Priority,
+    Clear,
+    NewTask,
+    Edit,
+    EditDescription,
+    EditInEditor,
+    CheckItem,
+    Pick,
+    Undo,
+    Help,
+    Insert,
+    Back,
+    Save,
+    NormalMode,
+}
+
+impl Action {
+    pub const ALL_TASKS: [Action; 35] = [
+        Action::Quit,
+        Action::Down,
+        Action::Up,
+        Action::PageDown,
+        Action::PageUp,
+        Action::First,
+        Action::Last,
+        Action::Complete,
+        Action::CycleStatus,
+        Action::Timer,
+        Action::ShowComplete,
+        Action::ShowWaiting,
+        Action::Delete,
+        Action::SnoozeDay,
+        Action::UnsnoozeDay,
+        Action::Snooze,
+        Action::SnoozeOverdue,
+        Action::Sort,
+        Action::Filter,
+        Action::OpenLink,
+        Action::Search,
+        Action::NextMatch,
+        Action::PrevMatch,
+        Action::Visual,
+        Action::Mark,
+        Action::Tags,
+        Action::Priority,
+        Action::Clear,
+        Action::NewTask,
+        Action::Edit,
+        Action::EditDescription,
+        Action::EditInEditor,
+        Action::CheckItem,
+        Action::Undo,
+        Action::Help,
+    ];
+
+    pub const NEW_TASK: [Action; 11] = [
+        Action::Insert,
+        Action::NormalMode,
+        Action::Down,
+        Action::Up,
+        Action::Pick,
+        Action::EditDescription,
+        Action::EditInEditor,
+        Action::Save,
+        Action::Back,
+        Action::Quit,
+        Action::Help,
+    ];
+
+    /// The name used in the config
+    pub fn name(&self) -> &'static str {
+        match self {
+            Action::Quit => "quit",
+            Action::Down => "down",
+            Action::Up => "up",
+            Action::PageDown => "page_down",
+            Action::PageUp => "page_up",
+            Action::First => "first",
+            Action::Last => "last",
+            Action::Complete => "complete",
+            Action::CycleStatus => "cycle_status",
+            Action::Timer => "timer",
+            Action::ShowComplete => "show_complete",
+            Action::ShowWaiting => "show_waiting",
+            Action::Delete => "delete",
+            Action::SnoozeDay => "snooze_day",
+            Action::UnsnoozeDay => "unsnooze_day",
+            Action::Snooze => "snooze",
+            Action::SnoozeOverdue => "snooze_overdue",
+            Action::Sort => "sort",
+            Action::Filter => "filter",
+            Action::OpenLink => "open_link",
+            Action::Search => "search",
+            Action::NextMatch => "next_match",
+            Action::PrevMatch => "prev_match",
+            Action::Visual => "visual",
+            Action::Mark => "mark",
+            Action::Tags => "tags",
+            Action::Priority => "priority",
+            Action::Clear => "clear",
+            Action::NewTask => "new_task",
+            Action::Edit => "edit",
+            Action::EditDescription => "edit_description",
+            Action::EditInEditor => "edit_in_editor",
+            Action::CheckItem => "check_item",
+            Action::Pick => "pick",
+            Action::Undo => "undo",
+            Action::Help => "help",
+            Action::Insert => "insert",
+            Action::Back => "back",
+            Action::Save => "save",
+            Action::NormalMode => "normal_mode",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Action::Quit => "quit",
+            Action::Down => "move down",
+            Action::Up => "move up",
+            Action::PageDown => "page down",
+            Action::PageUp => "page up",
+            Action::First => "go to the first task",
+            Action::Last => "go to the last task",
+            Action::Complete => "complete or reopen",
+            Action::CycleStatus => "cycle the status",
+            Action::Timer => "start or stop the timer",
+            Action::ShowComplete => "show or hide complete tasks",
+            Action::ShowWaiting => "show or hide waiting tasks",
+            Action::Delete => "delete",
+            Action::SnoozeDay => "snooze a day",
+            Action::UnsnoozeDay => "move a day earlier",
+            Action::Snooze => "snooze to a date",
+            Action::SnoozeOverdue => "move overdue tasks to today",
+            Action::Sort => "cycle the sort order",
+            Action::Filter => "filter",
+            Action::OpenLink => "open the link",
+            Action::Search => "search",
+            Action::NextMatch => "next search match",
+            Action::PrevMatch => "previous search match",
+            Action::Visual => "visual select",
+            Action::Mark => "mark",
+            Action::Tags => "add or remove tags",
+            Action::Priority => "set the priority",
+            Action::Clear => "clear the selection or search",
+            Action::NewTask => "new task",
+            Action::Edit => "edit",
+            Action::EditDescription => "edit the description in $EDITOR",
+            Action::EditInEditor => "edit the whole task in $EDITOR",
+            Action::CheckItem => "check or uncheck a checklist item",
+            Action::Pick => "pick a date or how the task repeats",
+            Action::Undo => "undo",
+            Action::Help => "show or hide this help",
+            Action::Insert => "edit the field",
+            Action::Back => "go back",
+            Action::Save => "save",
+            Action::NormalMode => "stop editing",
+        }
+    }
+
+    fn default_keys(&self) -> &'static [&'static str] {
+        match self {
+            Action::Quit => &["q"],
+            Action::Down => &["j", "down"],
+            Action::Up => &["k", "up"],
+            Action::PageDown => &["pagedown", "ctrl-d"],
+            Action::PageUp => &["pageup", "ctrl-u"],
+            Action::First => &["g", "home"],
+            Action::Last => &["G", "end"],
+            Action::Complete => &["x"],
+            Action::CycleStatus => &["c"],
+            Action::Timer => &["t"],
+            Action::ShowComplete => &["h"],
+            Action::ShowWaiting => &["w"],
+            Action::Delete => &["d"],
+            Action::SnoozeDay => &["+"],
+            Action::UnsnoozeDay => &["-"],
+            Action::Snooze => &["s"],
+            Action::SnoozeOverdue => &["O"],
+            Action::Sort => &["o"],
+            Action::Filter => &["f"],
+            Action::OpenLink => &["enter"],
+            Action::Search => &["/"],
+            Action::NextMatch => &["n"],
+            Action::PrevMatch => &["N"],
+            Action::Visual => &["v"],
+            Action::Mark => &["space"],
+            Action::Tags => &["T"],
+            Action::Priority => &["p"],
+            Action::Clear => &["esc"],
+            Action::NewTask => &["n"],
+            Action::Edit => &["e"],
+            Action::EditDescription => &["D"],
+            Action::EditInEditor => &["E"],
+            Action::CheckItem => &["X"],
+            Action::Pick => &["p"],
+            Action::Undo => &["u"],
+            Action::Help => &["?"],
+            Action::Insert => &["i"],
+            Action::Back => &["b"],
+            Action::Save => &["enter"],
+            Action::NormalMode => &["esc"],
+        }
+    }
+
+    /// Search actions only apply while a search is active, so they can share keys with the rest
+    fn is_search(&self) -> bool {
+        matches!(self, Action::NextMatch | Action::PrevMatch)
+    }
+}
+
+/// The keys bound to each action of a page
+pub struct Keymap {
+    pub bindings: Vec<(Action, Vec<Key>)>,
+}
+
+impl Keymap {
+    pub fn all_tasks(keybindings: &Keybindings) -> Result<Keymap> {
+        Keymap::build("all_tasks", &Action::ALL_TASKS, &keybindings.all_tasks)
+    }
+
+    pub fn new_task(keybindings: &Keybindings) -> Result<Keymap> {
+        Keymap::build("new_task", &Action::NEW_TASK, &keybindings.new_task)
+    }
+
+    /// Applies the configured keys over the defaults and checks no key does two things
+    fn build(
+        page: &str,
+        actions: &[Action],
+        config: &BTreeMap<String, Vec<String>>,
+    ) -> Result<Keymap> {
+        for name in config.keys() {
+            if !actions.iter().any(|a| a.name() == name) {
+                return Err(anyhow!("Unknown action '{}' in keybindings.{}", name, page));
+            }
+        }
+
+        let mut bindings: Vec<(Action, Vec<Key>)> = vec![];
+        for action in actions {
+            let keys = match config.get(action.name()) {
+                Some(keys) => keys.iter().map(|k| Key::parse_from_str(k)).collect(),
+                None => action
+                    .default_keys()
+                    .iter()
+                    .map(|k| Key::parse_from_str(k))
+                    .collect::<Result<Vec<_>>>(),
+            }?;
+
+            for key in keys.iter() {
+                let conflict = bindings.iter().find(|(other, other_keys)| {
+                    other.is_search() == action.is_search() && other_keys.contains(key)
+                });
+                if let Some((other, _)) = conflict {
+                    return Err(anyhow!(
+                        "Key '{}' is bound to both '{}' and '{}' in keybindings.{}",
+                        key,
+                        other.name(),
+                        action.name(),
+                        page
+                    ));
+                }
+            }
+            bindings.push((*action, keys));
+        }
+
+        Ok(Keymap { bindings })
+    }
+
+    /// The action a key press triggers. Search actions win while `searching`
+    pub fn action_for(&self, event: &KeyEvent, searching: bool) -> Option<Action> {
+        let key = Key::from_event(event);
+        let mut actions = self
+            .bindings
+            .iter()
+            .filter(|(action, keys)| keys.contains(&key) && (searching || !action.is_search()))
+            .map(|(action, _)| *action);
+        let first = actions.next()?;
+        match actions.next() {
+            Some(second) if second.is_search() => Some(second),
+            _ => Some(first),
+        }
+    }
+
+    /// The keys of an action, e.g. `j/down`
+    pub fn keys_for(&self, action: Action) -> String {
+        self.bindings
+            .iter()
+            .find(|(a, _)| *a == action)
+            .map(|(_, keys)| keys.iter().map(|k| k.to_string()).collect::<Vec<_>>().join("/"))
+            .unwrap_or_default()
+    }
+
+    /// Every bound action with its keys, in page order
+    pub fn help(&self) -> Vec<(String, &'static str)> {
+        self.bindings
+            .iter()
+            .filter(|(_, keys)| !keys.is_empty())
+            .map(|(action, _)| (self.keys_for(*action), action.description()))
+            .collect()
+    }
+}
+
+/// The keymaps of every page, checked once at startup
+pub struct Keymaps {
+    pub all_tasks: Keymap,
+    pub new_task: Keymap,
+}
+
+impl Keymaps {
+    pub fn new(keybindings: &Keybindings) -> Result<Keymaps> {
+        Ok(Keymaps {
+            all_tasks: Keymap::all_tasks(keybindings)?,
+            new_task: Keymap::new_task(keybindings)?,
+        })
+    }
+}
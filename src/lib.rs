@@ -3,9 +3,20 @@ pub mod cli;
 pub mod ui;
 
 pub mod day_of_week;
+pub mod editor;
+pub mod keybindings;
+pub mod priority;
+pub mod query;
 pub mod repeat;
+pub mod search;
+pub mod sort;
+pub mod status;
 pub mod task;
 pub mod task_form;
+pub mod theme;
+pub mod time_entry;
+pub mod view;
 
 pub mod configuration;
+pub mod reminders;
 pub mod utils;
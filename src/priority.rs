@@ -0,0 +1,33 @@
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+
+#[derive(
+    Serialize, Deserialize, Clone, Copy, Debug, Eq, PartialEq, PartialOrd, Ord, Hash, ValueEnum,
+)]
+pub enum Priority {
+    Low,
+    Medium,
+    High,
+}
+
+impl Priority {
+    pub fn parse_from_str(s: &str) -> anyhow::Result<Option<Priority>> {
+        if s.trim().is_empty() {
+            return Ok(None);
+        }
+        Priority::from_str(s.trim(), true)
+            .map(Some)
+            .map_err(|_| anyhow::anyhow!("Invalid priority"))
+    }
+}
+
+impl Display for Priority {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Priority::Low => write!(f, "Low"),
+            Priority::Medium => write!(f, "Medium"),
+            Priority::High => write!(f, "High"),
+        }
+    }
+}
@@ -0,0 +1,496 @@
+use crate::configuration::Settings;
+use crate::priority::Priority;
+use crate::repeat::Repeat;
+use crate::status::Status;
+use crate::task::Task;
+use crate::utils;
+use anyhow::{anyhow, Result};
+use chrono::{Duration, Local, NaiveDate};
+use clap::ValueEnum;
+
+/// A parsed filter expression, e.g. `due<=+3d and not complete and (tag:work or name~deploy)`.
+///
+/// Conditions are `field op value`, where fields are `due`, `scheduled`, `created`,
+/// `name`, `desc`, `tag`, `status`, `id`, `estimate`, `priority` and `project`,
+/// and operators are `<`, `<=`, `>`, `>=`, `=`, `!=`, `:` (has/is) and `~`
+/// (contains). Text fields and `status` only take `=`, `!=`, `:` and `~`, and
+/// `!=` matches tasks without a value for the field. The keywords `complete`,
+/// `closed`, `open`, `overdue`, `waiting`, `repeats` and `running` can be used
+/// on their own. Conditions are combined with `and`, `or`, `not` and
+/// parentheses, and conditions next to each other are joined with `and`.
+pub enum Query {
+    And(Box<Query>, Box<Query>),
+    Or(Box<Query>, Box<Query>),
+    Not(Box<Query>),
+    Condition(Condition),
+}
+
+pub enum Condition {
+    Keyword(Keyword),
+    Due(Op, NaiveDate),
+    Scheduled(Op, NaiveDate),
+    Name(Op, String),
+    Description(Op, String),
+    Tag(Op, String),
+    Status(Op, Status),
+    Id(Op, usize),
+    Estimate(Op, Duration),
+    Priority(Op, Priority),
+    Project(Op, String),
+    Created(Op, NaiveDate),
+}
+
+pub enum Keyword {
+    Complete,
+    Closed,
+    Open,
+    Overdue,
+    Waiting,
+    Repeats,
+    Running,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Op {
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Eq,
+    Ne,
+    Has,
+    Contains,
+}
+
+impl Op {
+    fn is_ordering(&self) -> bool {
+        matches!(self, Op::Lt | Op::Le | Op::Gt | Op::Ge)
+    }
+
+    fn compare<T: PartialOrd>(&self, a: &T, b: &T) -> bool {
+        match self {
+            Op::Lt => a < b,
+            Op::Le => a <= b,
+            Op::Gt => a > b,
+            Op::Ge => a >= b,
+            Op::Eq | Op::Has | Op::Contains => a == b,
+            Op::Ne => a != b,
+        }
+    }
+
+    fn compare_text(&self, text: &str, value: &str) -> bool {
+        let text = text.to_lowercase();
+        match self {
+            Op::Contains => text.contains(value),
+            Op::Ne => text != value,
+            _ => text == value,
+        }
+    }
+}
+
+#[derive(PartialEq, Debug)]
+enum Token {
+    Word(String),
+    Op(String),
+    LParen,
+    RParen,
+}
+
+const OP_CHARS: &str = "<>=!~:";
+
+fn tokenize(s: &str) -> Result<Vec<Token>> {
+    let mut tokens = vec![];
+    let mut chars = s.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c == '(' {
+            chars.next();
+            tokens.push(Token::LParen);
+        } else if c == ')' {
+            chars.next();
+            tokens.push(Token::RParen);
+        } else if c == '"' {
+            chars.next();
+            let mut word = String::new();
+            loop {
+                match chars.next() {
+                    Some('"') => break,
+                    Some(c) => word.push(c),
+                    None => return Err(anyhow!("Missing closing quote")),
+                }
+            }
+            tokens.push(Token::Word(word));
+        } else if OP_CHARS.contains(c) {
+            let mut op = String::new();
+            while let Some(&c) = chars.peek() {
+                if !OP_CHARS.contains(c) {
+                    break;
+                }
+                op.push(c);
+                chars.next();
+            }
+            tokens.push(Token::Op(op));
+        } else {
+            let mut word = String::new();
+            while let Some(&c) = chars.peek() {
+                if c.is_whitespace() || c == '(' || c == ')' || c == '"' || OP_CHARS.contains(c) {
+                    break;
+                }
+                word.push(c);
+                chars.next();
+            }
+            tokens.push(Token::Word(word));
+        }
+    }
+
+    Ok(tokens)
+}
+
+struct Parser<'a> {
+    tokens: Vec<Token>,
+    pos: usize,
+    settings: &'a Settings,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<&Token> {
+        self.pos += 1;
+        self.tokens.get(self.pos - 1)
+    }
+
+    fn peek_word(&self, word: &str) -> bool {
+        matches!(self.peek(), Some(Token::Word(w)) if w.eq_ignore_ascii_case(word))
+    }
+
+    fn parse_or(&mut self) -> Result<Query> {
+        let mut query = self.parse_and()?;
+        while self.peek_word("or") {
+            self.next();
+            let rhs = self.parse_and()?;
+            query = Query::Or(Box::new(query), Box::new(rhs));
+        }
+        Ok(query)
+    }
+
+    fn parse_and(&mut self) -> Result<Query> {
+        let mut query = self.parse_unary()?;
+        loop {
+            if self.peek_word("and") {
+                self.next();
+            } else if self.peek().is_none()
+                || self.peek() == Some(&Token::RParen)
+                || self.peek_word("or")
+            {
+                break;
+            }
+            let rhs = self.parse_unary()?;
+            query = Query::And(Box::new(query), Box::new(rhs));
+        }
+        Ok(query)
+    }
+
+    fn parse_unary(&mut self) -> Result<Query> {
+        if self.peek_word("not") {
+            self.next();
+            return Ok(Query::Not(Box::new(self.parse_unary()?)));
+        }
+
+        match self.next() {
+            Some(Token::LParen) => {
+                let query = self.parse_or()?;
+                match self.next() {
+                    Some(Token::RParen) => Ok(query),
+                    _ => Err(anyhow!("Missing closing parenthesis")),
+                }
+            }
+            Some(Token::Word(word)) => {
+                let word = word.to_lowercase();
+                self.parse_condition(word).map(Query::Condition)
+            }
+            Some(token) => Err(anyhow!("Unexpected {:?}", token)),
+            None => Err(anyhow!("Unexpected end of filter")),
+        }
+    }
+
+    fn parse_condition(&mut self, field: String) -> Result<Condition> {
+        let symbol = match self.peek() {
+            Some(Token::Op(op)) => op.clone(),
+            _ => return parse_keyword(&field).map(Condition::Keyword),
+        };
+        self.next();
+
+        let op = match symbol.as_str() {
+            "<" => Op::Lt,
+            "<=" => Op::Le,
+            ">" => Op::Gt,
+            ">=" => Op::Ge,
+            "=" | "==" => Op::Eq,
+            "!=" => Op::Ne,
+            ":" => Op::Has,
+            "~" => Op::Contains,
+            _ => return Err(anyhow!("Unknown operator '{}'", symbol)),
+        };
+        let value = match self.next() {
+            Some(Token::Word(value)) => value.clone(),
+            _ => return Err(anyhow!("Expected a value after '{}'", field)),
+        };
+
+        let text_fields = ["name", "desc", "description", "tag", "project", "status"];
+        if op.is_ordering() && text_fields.contains(&field.as_str()) {
+            return Err(anyhow!(
+                "Operator '{}' doesn't work on '{}', use '=', '!=', ':' or '~'",
+                symbol,
+                field
+            ));
+        }
+
+        let condition = match field.as_str() {
+            "due" | "date" => Condition::Due(op, self.parse_day(&value)?),
+            "scheduled" | "wait" => Condition::Scheduled(op, self.parse_day(&value)?),
+            "name" => Condition::Name(op, value.to_lowercase()),
+            "desc" | "description" => Condition::Description(op, value.to_lowercase()),
+            "tag" => Condition::Tag(op, value.to_lowercase()),
+            "status" => {
+                let status = Status::from_str(&value, true)
+                    .map_err(|_| anyhow!("Unknown status '{}'", value))?;
+                Condition::Status(op, status)
+            }
+            "id" => Condition::Id(op, value.parse()?),
+            "estimate" => Condition::Estimate(op, utils::parse_duration(&value)?),
+            "priority" => {
+                let priority = Priority::from_str(&value, true)
+                    .map_err(|_| anyhow!("Unknown priority '{}'", value))?;
+                Condition::Priority(op, priority)
+            }
+            "project" => Condition::Project(op, value.to_lowercase()),
+            "created" => Condition::Created(op, self.parse_day(&value)?),
+            _ => return Err(anyhow!("Unknown field '{}'", field)),
+        };
+        Ok(condition)
+    }
+
+    fn parse_day(&self, value: &str) -> Result<NaiveDate> {
+        let date = utils::parse_relative_date(value, &Local::now(), self.settings)
+            .map_err(|_| anyhow!("Unable to parse date '{}'", value))?;
+        Ok(date.date_naive())
+    }
+}
+
+fn parse_keyword(word: &str) -> Result<Keyword> {
+    match word {
+        "complete" | "done" => Ok(Keyword::Complete),
+        "closed" => Ok(Keyword::Closed),
+        "open" => Ok(Keyword::Open),
+        "overdue" => Ok(Keyword::Overdue),
+        "waiting" => Ok(Keyword::Waiting),
+        "repeats" => Ok(Keyword::Repeats),
+        "running" => Ok(Keyword::Running),
+        _ => Err(anyhow!("Unknown keyword '{}'", word)),
+    }
+}
+
+impl Query {
+    pub fn parse(s: &str, settings: &Settings) -> Result<Query> {
+        let mut parser = Parser {
+            tokens: tokenize(s)?,
+            pos: 0,
+            settings,
+        };
+        let query = parser.parse_or()?;
+        if let Some(token) = parser.peek() {
+            return Err(anyhow!("Unexpected {:?}", token));
+        }
+        Ok(query)
+    }
+
+    pub fn matches(&self, task: &Task) -> bool {
+        match self {
+            Query::And(a, b) => a.matches(task) && b.matches(task),
+            Query::Or(a, b) => a.matches(task) || b.matches(task),
+            Query::Not(q) => !q.matches(task),
+            Query::Condition(condition) => condition.matches(task),
+        }
+    }
+}
+
+impl Condition {
+    pub fn matches(&self, task: &Task) -> bool {
+        match self {
+            Condition::Keyword(keyword) => match keyword {
+                Keyword::Complete => task.is_complete(),
+                Keyword::Closed => task.status.is_closed(),
+                Keyword::Open => !task.status.is_closed(),
+                Keyword::Overdue => task.is_overdue(),
+                Keyword::Waiting => task.is_waiting(),
+                Keyword::Repeats => task.repeats != Repeat::Never,
+                Keyword::Running => task.is_timer_running(),
+            },
+            Condition::Due(op, date) => op.compare(&task.date.date_naive(), date),
+            Condition::Scheduled(op, date) => match task.scheduled {
+                Some(scheduled) => op.compare(&scheduled.date_naive(), date),
+                None => *op == Op::Ne,
+            },
+            Condition::Name(op, name) => op.compare_text(&task.name, name),
+            Condition::Description(op, desc) => {
+                let description = task.description.clone().unwrap_or_default();
+                op.compare_text(&description, desc)
+            }
+            Condition::Tag(op, tag) => {
+                let has_tag = task.tags.iter().any(|t| match op {
+                    Op::Contains => t.to_lowercase().contains(tag),
+                    _ => t.to_lowercase() == *tag,
+                });
+                if *op == Op::Ne {
+                    !has_tag
+                } else {
+                    has_tag
+                }
+            }
+            Condition::Status(op, status) => match op {
+                Op::Ne => task.status != *status,
+                _ => task.status == *status,
+            },
+            Condition::Id(op, id) => op.compare(&task.get_id(), id),
+            Condition::Estimate(op, estimate) => match task.estimate {
+                Some(task_estimate) => op.compare(&task_estimate, estimate),
+                None => *op == Op::Ne,
+            },
+            Condition::Priority(op, priority) => match task.priority {
+                Some(task_priority) => op.compare(&task_priority, priority),
+                None => *op == Op::Ne,
+            },
+            Condition::Project(op, project) => match &task.project {
+                Some(task_project) => op.compare_text(task_project, project),
+                None => *op == Op::Ne,
+            },
+            Condition::Created(op, date) => match task.created {
+                Some(created) => op.compare(&created.date_naive(), date),
+                None => *op == Op::Ne,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(s: &str) -> Result<Query> {
+        Query::parse(s, &Settings::with_db_file(String::new()))
+    }
+
+    fn error(s: &str) -> String {
+        match parse(s) {
+            Ok(_) => panic!("'{}' should not parse", s),
+            Err(e) => e.to_string(),
+        }
+    }
+
+    fn task(name: &str, tags: &[&str]) -> Task {
+        let mut task = Task::new();
+        task.set_name(name.to_string());
+        task.set_tags(tags.iter().map(|tag| tag.to_string()).collect());
+        task
+    }
+
+    fn word(s: &str) -> Token {
+        Token::Word(s.to_string())
+    }
+
+    fn op(s: &str) -> Token {
+        Token::Op(s.to_string())
+    }
+
+    #[test]
+    fn tokenizes_conditions_and_parentheses() {
+        let tokens = tokenize("due<=+3d and (tag:work)").unwrap();
+        assert_eq!(
+            tokens,
+            vec![
+                word("due"),
+                op("<="),
+                word("+3d"),
+                word("and"),
+                Token::LParen,
+                word("tag"),
+                op(":"),
+                word("work"),
+                Token::RParen,
+            ]
+        );
+    }
+
+    #[test]
+    fn tokenizes_quoted_values() {
+        let tokens = tokenize("name=\"buy milk (2l)\"").unwrap();
+        assert_eq!(tokens, vec![word("name"), op("="), word("buy milk (2l)")]);
+        assert_eq!(error("name=\"buy milk"), "Missing closing quote");
+    }
+
+    #[test]
+    fn matches_quoted_values() {
+        let query = parse("name=\"Buy milk\"").unwrap();
+        assert!(query.matches(&task("buy milk", &[])));
+        assert!(!query.matches(&task("buy", &[])));
+    }
+
+    #[test]
+    fn and_binds_tighter_than_or() {
+        let query = parse("tag:a or tag:b and tag:c").unwrap();
+        assert!(query.matches(&task("", &["a"])));
+        assert!(query.matches(&task("", &["b", "c"])));
+        assert!(!query.matches(&task("", &["b"])));
+
+        let query = parse("(tag:a or tag:b) and tag:c").unwrap();
+        assert!(!query.matches(&task("", &["a"])));
+        assert!(query.matches(&task("", &["a", "c"])));
+    }
+
+    #[test]
+    fn joins_neighbouring_conditions_with_and() {
+        let query = parse("tag:a not tag:b").unwrap();
+        assert!(query.matches(&task("", &["a"])));
+        assert!(!query.matches(&task("", &["a", "b"])));
+        assert!(!query.matches(&task("", &[])));
+    }
+
+    #[test]
+    fn not_equal_matches_missing_values() {
+        let no_values = task("", &[]);
+        assert!(parse("project!=home").unwrap().matches(&no_values));
+        assert!(parse("scheduled!=today").unwrap().matches(&no_values));
+        assert!(parse("priority!=high").unwrap().matches(&no_values));
+        assert!(!parse("scheduled=today").unwrap().matches(&no_values));
+    }
+
+    #[test]
+    fn rejects_ordering_text_fields() {
+        for field in ["name", "project", "tag", "status"] {
+            let message = error(&format!("{}<zzz", field));
+            assert_eq!(
+                message,
+                format!("Operator '<' doesn't work on '{}', use '=', '!=', ':' or '~'", field)
+            );
+        }
+        assert!(parse("due<today").is_ok());
+    }
+
+    #[test]
+    fn reports_errors() {
+        assert_eq!(error("(tag:a"), "Missing closing parenthesis");
+        assert_eq!(error("tag:a)"), "Unexpected RParen");
+        assert_eq!(error("tag:a and"), "Unexpected end of filter");
+        assert_eq!(error("tag:"), "Expected a value after 'tag'");
+        assert_eq!(error("tag=>a"), "Unknown operator '=>'");
+        assert_eq!(error("colour=red"), "Unknown field 'colour'");
+        assert_eq!(error("finished"), "Unknown keyword 'finished'");
+        assert_eq!(error("status=later"), "Unknown status 'later'");
+        assert_eq!(error("due<someday"), "Unable to parse date 'someday'");
+    }
+}
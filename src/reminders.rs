@@ -0,0 +1,110 @@
+use crate::configuration::Settings;
+use crate::task::Task;
+use crate::utils;
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Duration, Local};
+use std::collections::HashSet;
+use std::fs;
+use std::process::Command;
+
+/// Reminders missed while the daemon wasn't running are still sent within this window
+const MISSED_REMINDER_WINDOW_HOURS: i64 = 24;
+
+pub struct Reminder {
+    pub task: Task,
+    pub fire_at: DateTime<Local>,
+}
+
+impl Reminder {
+    /// Identifies a reminder across restarts. Rescheduling a task creates new keys
+    pub fn key(&self) -> String {
+        format!("{}@{}", self.task.get_id(), self.fire_at.format("%+"))
+    }
+}
+
+/// Reminders that are due and haven't been delivered yet
+pub fn due_reminders(
+    tasks: &[Task],
+    now: DateTime<Local>,
+    delivered: &DeliveredReminders,
+) -> Vec<Reminder> {
+    let oldest = now - Duration::hours(MISSED_REMINDER_WINDOW_HOURS);
+    tasks
+        .iter()
+        .filter(|t| !t.status.is_closed())
+        .flat_map(|t| {
+            t.reminders.iter().filter_map(|offset| {
+                Some(Reminder {
+                    task: t.clone(),
+                    fire_at: t.date.checked_sub_signed(*offset)?,
+                })
+            })
+        })
+        .filter(|r| r.fire_at <= now && r.fire_at > oldest)
+        .filter(|r| !delivered.contains(r))
+        .collect()
+}
+
+/// Runs the configured notifier for a reminder
+pub fn notify(reminder: &Reminder, settings: &Settings) -> Result<()> {
+    let task = &reminder.task;
+    let date = utils::date_to_display_str(&task.date, settings);
+    let description = task.description.clone().unwrap_or_default();
+    let args: Vec<String> = settings
+        .notifier
+        .args
+        .iter()
+        .map(|arg| {
+            arg.replace("{name}", &task.name)
+                .replace("{date}", &date)
+                .replace("{id}", &task.get_id().to_string())
+                .replace("{description}", &description)
+        })
+        .collect();
+
+    let status = Command::new(&settings.notifier.command).args(args).status()?;
+    if !status.success() {
+        return Err(anyhow!("Notifier exited with {}", status));
+    }
+    Ok(())
+}
+
+/// Keys of the reminders already delivered, persisted next to the settings
+pub struct DeliveredReminders {
+    keys: HashSet<String>,
+    file: String,
+}
+
+impl DeliveredReminders {
+    pub fn load(file: String) -> DeliveredReminders {
+        let keys = fs::read_to_string(&file)
+            .ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default();
+        DeliveredReminders { keys, file }
+    }
+
+    pub fn contains(&self, reminder: &Reminder) -> bool {
+        self.keys.contains(&reminder.key())
+    }
+
+    pub fn insert(&mut self, reminder: &Reminder) {
+        self.keys.insert(reminder.key());
+    }
+
+    /// Forgets reminders too old to ever be sent again
+    pub fn prune(&mut self, now: DateTime<Local>) {
+        let oldest = now - Duration::hours(MISSED_REMINDER_WINDOW_HOURS);
+        self.keys.retain(|key| {
+            let fire_at = key.split_once('@').map(|(_, date)| date).unwrap_or_default();
+            match DateTime::parse_from_str(fire_at, "%+") {
+                Ok(fire_at) => fire_at > oldest,
+                Err(_) => false,
+            }
+        });
+    }
+
+    pub fn save(&self) {
+        utils::write_json(&self.file, &self.keys);
+    }
+}
@@ -39,6 +39,18 @@ impl Repeat {
             }
         }
     }
+
+    /// Reads like a sentence, e.g. "every week" or "every Mon, Wed"
+    pub fn describe(&self) -> String {
+        match self {
+            Repeat::Never => "never".to_string(),
+            Repeat::Daily => "every day".to_string(),
+            Repeat::Weekly => "every week".to_string(),
+            Repeat::Monthly => "every month".to_string(),
+            Repeat::Yearly => "every year".to_string(),
+            Repeat::DaysOfWeek(days) => format!("every {}", days.iter().join(", ")),
+        }
+    }
 }
 
 impl Display for Repeat {
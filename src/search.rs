@@ -0,0 +1,93 @@
+use crate::task::Task;
+use std::cmp::Reverse;
+
+/// A fuzzy match of a query inside some text
+pub struct Match {
+    pub score: i64,
+    /// Char indices of the matched characters in the text
+    pub positions: Vec<usize>,
+}
+
+fn lowercase_chars(s: &str) -> Vec<char> {
+    s.chars()
+        .map(|c| c.to_lowercase().next().unwrap_or(c))
+        .collect()
+}
+
+fn is_word_start(text: &[char], idx: usize) -> bool {
+    idx == 0 || !text[idx - 1].is_alphanumeric()
+}
+
+/// Case-insensitive match of `query` in `text`. Exact substrings rank first,
+/// then the query's characters in order, preferring consecutive characters
+/// and the start of words.
+pub fn fuzzy_match(query: &str, text: &str) -> Option<Match> {
+    let query = lowercase_chars(query.trim());
+    let text = lowercase_chars(text);
+    if query.is_empty() || query.len() > text.len() {
+        return None;
+    }
+
+    // Exact substring
+    if let Some(start) = text.windows(query.len()).position(|w| w == query.as_slice()) {
+        let boundary_bonus = if is_word_start(&text, start) { 50 } else { 0 };
+        // Shorter texts mean the query covers more of them
+        let extra_chars = (text.len() - query.len()) as i64;
+        return Some(Match {
+            score: 1000 + boundary_bonus - start as i64 - extra_chars.min(100),
+            positions: (start..start + query.len()).collect(),
+        });
+    }
+
+    // Subsequence
+    let mut positions = vec![];
+    let mut score = 0;
+    let mut query_idx = 0;
+    for (idx, c) in text.iter().enumerate() {
+        if query_idx == query.len() {
+            break;
+        }
+        if *c != query[query_idx] {
+            continue;
+        }
+
+        score += 10;
+        if positions.last().map(|&last| last + 1 == idx).unwrap_or(false) {
+            score += 15;
+        }
+        if is_word_start(&text, idx) {
+            score += 20;
+        }
+        positions.push(idx);
+        query_idx += 1;
+    }
+
+    if query_idx < query.len() {
+        return None;
+    }
+
+    // Prefer matches that are close together
+    let spread = (positions[positions.len() - 1] - positions[0]) as i64;
+    Some(Match {
+        score: score - spread,
+        positions,
+    })
+}
+
+/// Scores a task against a query, favouring matches in its name
+pub fn score_task(query: &str, task: &Task) -> Option<i64> {
+    let name_score = fuzzy_match(query, &task.name).map(|m| m.score * 2);
+    let description = task.description.clone().unwrap_or_default();
+    let description_score = fuzzy_match(query, &description).map(|m| m.score);
+    name_score.max(description_score)
+}
+
+/// The tasks matching a query, best matches first
+pub fn search<'a>(query: &str, tasks: &'a [Task]) -> Vec<&'a Task> {
+    let mut results: Vec<(i64, &Task)> = tasks
+        .iter()
+        .filter_map(|t| score_task(query, t).map(|score| (score, t)))
+        .collect();
+    results.sort_by_key(|(score, _)| Reverse(*score));
+    results.into_iter().map(|(_, t)| t).collect()
+}
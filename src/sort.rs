@@ -0,0 +1,239 @@
+use crate::status::Status;
+use crate::task::Task;
+use anyhow::{anyhow, Result};
+use std::cmp::{Ordering, Reverse};
+use std::fmt::{Display, Formatter};
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum SortField {
+    Due,
+    Scheduled,
+    Priority,
+    Created,
+    Name,
+    Project,
+    Tag,
+    Status,
+    Estimate,
+    Id,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct SortKey {
+    pub field: SortField,
+    pub descending: bool,
+}
+
+/// A list of sort keys like `due,priority,-created,name`. A leading `-` reverses a key.
+/// Tasks missing a field sort last, and `priority` puts the highest priority first.
+#[derive(Clone, PartialEq, Eq)]
+pub struct SortOrder {
+    pub keys: Vec<SortKey>,
+}
+
+impl SortField {
+    pub fn parse_from_str(s: &str) -> Result<SortField> {
+        match s.trim().to_lowercase().as_str() {
+            "due" | "date" => Ok(SortField::Due),
+            "scheduled" => Ok(SortField::Scheduled),
+            "priority" => Ok(SortField::Priority),
+            "created" => Ok(SortField::Created),
+            "name" => Ok(SortField::Name),
+            "project" => Ok(SortField::Project),
+            "tag" | "tags" => Ok(SortField::Tag),
+            "status" => Ok(SortField::Status),
+            "estimate" => Ok(SortField::Estimate),
+            "id" => Ok(SortField::Id),
+            _ => Err(anyhow!("Unknown sort key '{}'", s.trim())),
+        }
+    }
+
+    fn compare(&self, a: &Task, b: &Task, descending: bool) -> Ordering {
+        let ordering = match self {
+            SortField::Due => a.date.cmp(&b.date),
+            SortField::Name => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
+            SortField::Status => status_rank(a.status).cmp(&status_rank(b.status)),
+            SortField::Id => a.id.cmp(&b.id),
+            SortField::Scheduled => return compare_options(&a.scheduled, &b.scheduled, descending),
+            SortField::Priority => {
+                let (a, b) = (a.priority.map(Reverse), b.priority.map(Reverse));
+                return compare_options(&a, &b, descending);
+            }
+            SortField::Created => return compare_options(&a.created, &b.created, descending),
+            SortField::Project => return compare_options(&a.project, &b.project, descending),
+            SortField::Tag => return compare_options(&a.tags.first(), &b.tags.first(), descending),
+            SortField::Estimate => return compare_options(&a.estimate, &b.estimate, descending),
+        };
+        if descending {
+            ordering.reverse()
+        } else {
+            ordering
+        }
+    }
+}
+
+impl Display for SortField {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SortField::Due => write!(f, "due"),
+            SortField::Scheduled => write!(f, "scheduled"),
+            SortField::Priority => write!(f, "priority"),
+            SortField::Created => write!(f, "created"),
+            SortField::Name => write!(f, "name"),
+            SortField::Project => write!(f, "project"),
+            SortField::Tag => write!(f, "tag"),
+            SortField::Status => write!(f, "status"),
+            SortField::Estimate => write!(f, "estimate"),
+            SortField::Id => write!(f, "id"),
+        }
+    }
+}
+
+/// Orders `Some` values before `None`, whichever the direction
+fn compare_options<T: Ord>(a: &Option<T>, b: &Option<T>, descending: bool) -> Ordering {
+    match (a, b) {
+        (Some(a), Some(b)) if descending => b.cmp(a),
+        (Some(a), Some(b)) => a.cmp(b),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    }
+}
+
+fn status_rank(status: Status) -> usize {
+    Status::ALL.iter().position(|s| *s == status).unwrap()
+}
+
+impl SortOrder {
+    pub fn parse_from_str(s: &str) -> Result<SortOrder> {
+        let keys = s
+            .split(',')
+            .filter(|k| !k.trim().is_empty())
+            .map(|k| {
+                let k = k.trim();
+                let (descending, field) = match k.strip_prefix('-') {
+                    Some(field) => (true, field),
+                    None => (false, k),
+                };
+                Ok(SortKey {
+                    field: SortField::parse_from_str(field)?,
+                    descending,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        if keys.is_empty() {
+            return Err(anyhow!("Sort order cannot be empty"));
+        }
+        Ok(SortOrder { keys })
+    }
+
+    /// The field tasks are grouped by
+    pub fn primary(&self) -> SortField {
+        self.keys[0].field
+    }
+
+    pub fn compare(&self, a: &Task, b: &Task) -> Ordering {
+        for key in self.keys.iter() {
+            let ordering = key.field.compare(a, b, key.descending);
+            if ordering != Ordering::Equal {
+                return ordering;
+            }
+        }
+        Ordering::Equal
+    }
+
+    pub fn sort(&self, tasks: &mut [Task]) {
+        tasks.sort_by(|a, b| self.compare(a, b));
+    }
+}
+
+impl Default for SortOrder {
+    fn default() -> SortOrder {
+        SortOrder {
+            keys: vec![SortKey {
+                field: SortField::Due,
+                descending: false,
+            }],
+        }
+    }
+}
+
+impl Display for SortOrder {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let keys: Vec<String> = self
+            .keys
+            .iter()
+            .map(|k| {
+                let prefix = if k.descending { "-" } else { "" };
+                format!("{}{}", prefix, k.field)
+            })
+            .collect();
+        write!(f, "{}", keys.join(","))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::priority::Priority;
+
+    fn task(name: &str, priority: Option<Priority>) -> Task {
+        let mut task = Task::new();
+        task.set_name(name.to_string());
+        task.set_priority(priority);
+        task
+    }
+
+    fn sorted_names(order: &str, mut tasks: Vec<Task>) -> Vec<String> {
+        SortOrder::parse_from_str(order).unwrap().sort(&mut tasks);
+        tasks.into_iter().map(|task| task.name).collect()
+    }
+
+    #[test]
+    fn parses_keys_and_directions() {
+        let order = SortOrder::parse_from_str(" Priority, -created ,name").unwrap();
+        assert!(order.primary() == SortField::Priority);
+        let descending: Vec<bool> = order.keys.iter().map(|key| key.descending).collect();
+        assert_eq!(descending, [false, true, false]);
+        assert_eq!(order.to_string(), "priority,-created,name");
+    }
+
+    #[test]
+    fn rejects_unknown_and_empty_orders() {
+        let error = SortOrder::parse_from_str("due,colour").err().unwrap();
+        assert_eq!(error.to_string(), "Unknown sort key 'colour'");
+        let error = SortOrder::parse_from_str(" , ").err().unwrap();
+        assert_eq!(error.to_string(), "Sort order cannot be empty");
+    }
+
+    #[test]
+    fn sorts_by_name_in_both_directions() {
+        let tasks = || vec![task("b", None), task("C", None), task("a", None)];
+        assert_eq!(sorted_names("name", tasks()), ["a", "b", "C"]);
+        assert_eq!(sorted_names("-name", tasks()), ["C", "b", "a"]);
+    }
+
+    #[test]
+    fn puts_high_priority_first_and_missing_values_last() {
+        let tasks = || {
+            vec![
+                task("none", None),
+                task("low", Some(Priority::Low)),
+                task("high", Some(Priority::High)),
+            ]
+        };
+        assert_eq!(sorted_names("priority", tasks()), ["high", "low", "none"]);
+        assert_eq!(sorted_names("-priority", tasks()), ["low", "high", "none"]);
+    }
+
+    #[test]
+    fn breaks_ties_with_later_keys() {
+        let tasks = vec![
+            task("b", Some(Priority::High)),
+            task("c", None),
+            task("a", Some(Priority::High)),
+        ];
+        assert_eq!(sorted_names("priority,name", tasks), ["a", "b", "c"]);
+    }
+}
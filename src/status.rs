@@ -0,0 +1,65 @@
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Eq, PartialEq, Hash, ValueEnum)]
+pub enum Status {
+    Todo,
+    InProgress,
+    /// Waiting on someone else
+    Blocked,
+    Complete,
+    Cancelled,
+}
+
+impl Status {
+    pub const ALL: [Status; 5] = [
+        Status::Todo,
+        Status::InProgress,
+        Status::Blocked,
+        Status::Complete,
+        Status::Cancelled,
+    ];
+
+    /// Closed tasks are hidden along with complete ones
+    pub fn is_closed(&self) -> bool {
+        matches!(self, Status::Complete | Status::Cancelled)
+    }
+
+    pub fn next(&self) -> Status {
+        let idx = Self::ALL.iter().position(|s| s == self).unwrap();
+        Self::ALL[(idx + 1) % Self::ALL.len()]
+    }
+}
+
+impl Display for Status {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Status::Todo => write!(f, "Todo"),
+            Status::InProgress => write!(f, "In progress"),
+            Status::Blocked => write!(f, "Blocked"),
+            Status::Complete => write!(f, "Complete"),
+            Status::Cancelled => write!(f, "Cancelled"),
+        }
+    }
+}
+
+/// Accepts both a status and the `complete` boolean older databases stored
+pub fn deserialize_status<'de, D>(deserializer: D) -> Result<Status, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum StatusOrBool {
+        Status(Status),
+        Bool(bool),
+    }
+
+    let status = match StatusOrBool::deserialize(deserializer)? {
+        StatusOrBool::Status(status) => status,
+        StatusOrBool::Bool(true) => Status::Complete,
+        StatusOrBool::Bool(false) => Status::Todo,
+    };
+    Ok(status)
+}
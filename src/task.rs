@@ -1,6 +1,10 @@
+use crate::priority::Priority;
+use crate::status::{deserialize_status, Status};
+use crate::time_entry::TimeEntry;
+use crate::utils;
 use crate::{day_of_week::DayOfWeek, repeat::Repeat};
 use anyhow::Result;
-use chrono::{Datelike, Days, Local, Months, DateTime, TimeZone};
+use chrono::{Datelike, Days, Duration, Local, Months, DateTime, TimeZone};
 use serde::{Deserialize, Serialize};
 
 pub fn serialize_dt<S>(date: &DateTime<Local>, serializer: S) -> Result<S::Ok, S::Error>
@@ -20,15 +24,107 @@ where
     Ok(dt)
 }
 
+pub fn serialize_opt_dt<S>(date: &Option<DateTime<Local>>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    match date {
+        Some(date) => serialize_dt(date, serializer),
+        None => serializer.serialize_none(),
+    }
+}
+
+pub fn deserialize_opt_dt<'de, D>(deserializer: D) -> Result<Option<DateTime<Local>>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let s = Option::<String>::deserialize(deserializer)?;
+    let dt = s.map(|s| Local.datetime_from_str(&s, "%+").unwrap());
+    Ok(dt)
+}
+
+pub fn serialize_opt_duration<S>(duration: &Option<Duration>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    match duration {
+        Some(duration) => serializer.serialize_i64(duration.num_seconds()),
+        None => serializer.serialize_none(),
+    }
+}
+
+pub fn deserialize_opt_duration<'de, D>(deserializer: D) -> Result<Option<Duration>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let seconds = Option::<i64>::deserialize(deserializer)?;
+    Ok(seconds.map(Duration::seconds))
+}
+
+pub fn serialize_durations<S>(durations: &[Duration], serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    let seconds: Vec<i64> = durations.iter().map(|d| d.num_seconds()).collect();
+    seconds.serialize(serializer)
+}
+
+pub fn deserialize_durations<'de, D>(deserializer: D) -> Result<Vec<Duration>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let seconds = Vec::<i64>::deserialize(deserializer)?;
+    Ok(seconds.into_iter().map(Duration::seconds).collect())
+}
+
 #[derive(Serialize, Deserialize, Clone)]
 pub struct Task {
     pub id: Option<usize>,
     pub name: String,
     #[serde(serialize_with = "serialize_dt", deserialize_with = "deserialize_dt")]
     pub date: DateTime<Local>,
+    /// The task stays hidden until this date, unless waiting tasks are shown
+    #[serde(
+        default,
+        serialize_with = "serialize_opt_dt",
+        deserialize_with = "deserialize_opt_dt"
+    )]
+    pub scheduled: Option<DateTime<Local>>,
     pub repeats: Repeat,
     pub description: Option<String>,
-    pub complete: bool,
+    #[serde(alias = "complete", deserialize_with = "deserialize_status")]
+    pub status: Status,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub time_entries: Vec<TimeEntry>,
+    /// How long the task is expected to take, stored in seconds
+    #[serde(
+        default,
+        serialize_with = "serialize_opt_duration",
+        deserialize_with = "deserialize_opt_duration"
+    )]
+    pub estimate: Option<Duration>,
+    /// How long before the due date to send reminders, stored in seconds
+    #[serde(
+        default,
+        serialize_with = "serialize_durations",
+        deserialize_with = "deserialize_durations"
+    )]
+    pub reminders: Vec<Duration>,
+    #[serde(default)]
+    pub priority: Option<Priority>,
+    #[serde(default)]
+    pub project: Option<String>,
+    /// URLs, file paths and `mailto:` addresses that go with the task
+    #[serde(default)]
+    pub links: Vec<String>,
+    #[serde(
+        default,
+        serialize_with = "serialize_opt_dt",
+        deserialize_with = "deserialize_opt_dt"
+    )]
+    pub created: Option<DateTime<Local>>,
 }
 
 impl Task {
@@ -37,9 +133,18 @@ impl Task {
             id: None,
             name: "".to_string(),
             date: Local::now(),
+            scheduled: None,
             repeats: Repeat::Never,
             description: None,
-            complete: false,
+            status: Status::Todo,
+            tags: vec![],
+            time_entries: vec![],
+            estimate: None,
+            reminders: vec![],
+            priority: None,
+            project: None,
+            links: vec![],
+            created: Some(Local::now()),
         }
     }
 
@@ -51,6 +156,35 @@ impl Task {
         self.date = date;
     }
 
+    pub fn is_overdue(&self) -> bool {
+        !self.status.is_closed() && self.date < Local::now()
+    }
+
+    /// Open and due within `window`, but not overdue yet
+    pub fn is_due_soon(&self, window: Duration) -> bool {
+        let now = Local::now();
+        !self.status.is_closed() && self.date >= now && self.date <= now + window
+    }
+
+    /// Moves the due date, shifting the scheduled date along with it
+    pub fn reschedule(&mut self, date: DateTime<Local>) {
+        if let Some(scheduled) = self.scheduled {
+            self.set_scheduled(Some(scheduled + (date - self.date)));
+        }
+        self.set_date(date);
+    }
+
+    pub fn set_scheduled(&mut self, scheduled: Option<DateTime<Local>>) {
+        self.scheduled = scheduled;
+    }
+
+    pub fn is_waiting(&self) -> bool {
+        match self.scheduled {
+            Some(scheduled) => scheduled > Local::now(),
+            None => false,
+        }
+    }
+
     pub fn set_repeats(&mut self, repeats: Repeat) {
         self.repeats = repeats;
     }
@@ -59,8 +193,85 @@ impl Task {
         self.description = Some(description);
     }
 
+    pub fn set_links(&mut self, links: Vec<String>) {
+        self.links = links;
+    }
+
+    /// The links in the description followed by the ones in `links`, without repeats
+    pub fn all_links(&self) -> Vec<String> {
+        let description = self.description.clone().unwrap_or_default();
+        let mut links = utils::extract_links(&description);
+        for link in &self.links {
+            if !links.contains(link) {
+                links.push(link.clone());
+            }
+        }
+        links
+    }
+
+    pub fn set_tags(&mut self, tags: Vec<String>) {
+        self.tags = tags;
+    }
+
+    pub fn set_estimate(&mut self, estimate: Option<Duration>) {
+        self.estimate = estimate;
+    }
+
+    pub fn set_reminders(&mut self, reminders: Vec<Duration>) {
+        self.reminders = reminders;
+    }
+
+    pub fn set_priority(&mut self, priority: Option<Priority>) {
+        self.priority = priority;
+    }
+
+    pub fn set_project(&mut self, project: Option<String>) {
+        self.project = project;
+    }
+
+    pub fn is_timer_running(&self) -> bool {
+        self.time_entries.iter().any(|e| e.is_running())
+    }
+
+    pub fn start_timer(&mut self) {
+        if !self.is_timer_running() {
+            self.time_entries.push(TimeEntry::start_now());
+        }
+    }
+
+    pub fn stop_timer(&mut self) {
+        let now = Local::now();
+        for entry in self.time_entries.iter_mut().filter(|e| e.is_running()) {
+            entry.end = Some(now);
+        }
+    }
+
+    pub fn total_time(&self) -> Duration {
+        self.time_entries
+            .iter()
+            .fold(Duration::zero(), |acc, e| acc + e.duration())
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.status == Status::Complete
+    }
+
+    pub fn set_status(&mut self, status: Status) -> Option<Task> {
+        match status {
+            Status::Complete => self.set_complete(),
+            _ => {
+                self.status = status;
+                if status.is_closed() {
+                    self.stop_timer();
+                }
+                None
+            }
+        }
+    }
+
     pub fn set_complete(&mut self) -> Option<Task> {
-        self.complete = true;
+        self.status = Status::Complete;
+        self.stop_timer();
         let date = match &self.repeats {
             Repeat::DaysOfWeek(days) => {
                 let mut new_date = None;
@@ -83,7 +294,7 @@ impl Task {
 
         if let Some(date) = date {
             let mut new_task = self.clone();
-            new_task.set_date(date);
+            new_task.reschedule(date);
             new_task.set_incomplete();
             Some(new_task)
         } else {
@@ -92,12 +303,12 @@ impl Task {
     }
 
     pub fn set_incomplete(&mut self) -> Option<Task> {
-        self.complete = false;
+        self.status = Status::Todo;
         None
     }
 
     pub fn toggle_complete(&mut self) -> Option<Task> {
-        if self.complete {
+        if self.is_complete() {
             self.set_incomplete()
         } else {
             self.set_complete()
@@ -1,15 +1,27 @@
-use anyhow::Context;
-use anyhow::Result;
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Duration, Local};
 
 use crate::configuration::Settings;
+use crate::priority::Priority;
 use crate::repeat::Repeat;
 use crate::task::Task;
 use crate::utils;
+use serde::{Deserialize, Serialize};
 
+/// A task as text, the way it's typed in the TUI, the CLI or an editor
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
 pub struct TaskForm {
     pub name: String,
     pub date: String,
+    pub scheduled: String,
     pub repeats: String,
+    pub tags: String,
+    pub estimate: String,
+    pub reminders: String,
+    pub project: String,
+    pub priority: String,
+    pub links: String,
     pub description: String,
 }
 
@@ -18,16 +30,53 @@ impl TaskForm {
         TaskForm {
             name: "".to_string(),
             date: "".to_string(),
+            scheduled: "".to_string(),
             repeats: "".to_string(),
             description: "".to_string(),
+            tags: "".to_string(),
+            estimate: "".to_string(),
+            reminders: "".to_string(),
+            project: "".to_string(),
+            priority: "".to_string(),
+            links: "".to_string(),
         }
     }
 
+    pub fn from_task(task: &Task, settings: &Settings) -> TaskForm {
+        let mut task_form = TaskForm::new();
+        task_form.name = task.name.to_string();
+        task_form.date = utils::date_to_input_str(&task.date, settings);
+        if let Some(scheduled) = &task.scheduled {
+            task_form.scheduled = utils::start_date_to_input_str(scheduled, settings);
+        }
+        task_form.repeats = task.repeats.to_string();
+        task_form.description = task.description.clone().unwrap_or_default();
+        task_form.tags = task.tags.join(",");
+        if let Some(estimate) = &task.estimate {
+            task_form.estimate = utils::format_duration(estimate).replace(' ', "");
+        }
+        task_form.reminders = utils::format_durations(&task.reminders).replace(' ', "");
+        task_form.project = task.project.clone().unwrap_or_default();
+        if let Some(priority) = &task.priority {
+            task_form.priority = priority.to_string();
+        }
+        task_form.links = task.links.join(", ");
+        task_form
+    }
+
     pub fn submit(&mut self, settings: &Settings) -> Result<Task> {
-        let mut task = Task::new();
+        self.submit_onto(Task::new(), settings)
+    }
 
-        let repeat = Repeat::parse_from_str(&self.repeats).context("Invalid repeat format")?;
-        let date = utils::parse_date(&self.date, &settings).unwrap_or(utils::get_today());
+    /// Applies the form to an existing task, keeping the fields the form doesn't cover
+    pub fn submit_onto(&mut self, mut task: Task, settings: &Settings) -> Result<Task> {
+        let repeat = self.parse_repeats()?;
+        let date = self.parse_date(settings)?;
+        let scheduled = self.parse_scheduled(settings)?;
+        let estimate = self.parse_estimate()?;
+        let reminders = self.parse_reminders()?;
+        let priority = Priority::parse_from_str(&self.priority)?;
+        let links = self.parse_links()?;
 
         if self.name.is_empty() {
             return Err(anyhow::anyhow!("Task name cannot be empty"));
@@ -35,9 +84,129 @@ impl TaskForm {
 
         task.set_name(self.name.clone());
         task.set_date(date);
+        task.set_scheduled(scheduled);
         task.set_repeats(repeat);
         task.set_description(self.description.clone());
+        task.set_tags(utils::parse_tags(&self.tags));
+        task.set_estimate(estimate);
+        task.set_reminders(reminders);
+        task.set_priority(priority);
+        task.set_links(links);
+        let project = self.project.trim();
+        task.set_project((!project.is_empty()).then(|| project.to_string()));
 
         Ok(task)
     }
+
+    /// What a field reads as, e.g. "Fri Mar 8 at 14:00" or "every Mon, Wed", or why it
+    /// doesn't parse. `None` when there's nothing worth showing
+    pub fn preview(&self, field: Field, settings: &Settings) -> Result<Option<String>> {
+        let preview = match field {
+            Field::Date => Some(utils::date_to_display_str(&self.parse_date(settings)?, settings)),
+            Field::Scheduled => self
+                .parse_scheduled(settings)?
+                .map(|scheduled| utils::start_date_to_display_str(&scheduled, settings)),
+            Field::Repeats => match self.parse_repeats()? {
+                Repeat::Never => None,
+                repeat => Some(repeat.describe()),
+            },
+            Field::Estimate => self
+                .parse_estimate()?
+                .map(|estimate| utils::format_duration(&estimate)),
+            Field::Reminders => {
+                let reminders = self.parse_reminders()?;
+                (!reminders.is_empty())
+                    .then(|| format!("{} before", utils::format_durations(&reminders)))
+            }
+            Field::Priority => {
+                Priority::parse_from_str(&self.priority)?;
+                None
+            }
+            Field::Links => match self.parse_links()?.len() {
+                0 => None,
+                1 => Some("1 link".to_string()),
+                n => Some(format!("{} links", n)),
+            },
+            Field::Name | Field::Description | Field::Tags | Field::Project => None,
+        };
+        Ok(preview)
+    }
+
+    /// An empty date is the end of today. Days like `tomorrow`, `fri` or `3d` are too
+    fn parse_date(&self, settings: &Settings) -> Result<DateTime<Local>> {
+        let today = utils::get_today();
+        match self.date.trim() {
+            "" => Ok(today),
+            date => utils::parse_date(&date.to_string(), settings)
+                .or_else(|_| utils::parse_relative_date(date, &today, settings))
+                .with_context(|| date_error("date", settings)),
+        }
+    }
+
+    fn parse_scheduled(&self, settings: &Settings) -> Result<Option<DateTime<Local>>> {
+        match self.scheduled.trim() {
+            "" => Ok(None),
+            scheduled => utils::parse_start_date(&scheduled.to_string(), settings)
+                .or_else(|_| {
+                    let today = utils::start_of_day(Local::now().date_naive());
+                    utils::parse_relative_date(scheduled, &today, settings)
+                })
+                .map(Some)
+                .with_context(|| date_error("scheduled date", settings)),
+        }
+    }
+
+    fn parse_repeats(&self) -> Result<Repeat> {
+        Repeat::parse_from_str(self.repeats.trim()).context("Invalid repeat format")
+    }
+
+    fn parse_estimate(&self) -> Result<Option<Duration>> {
+        match self.estimate.trim() {
+            "" => Ok(None),
+            estimate => Ok(Some(utils::parse_duration(estimate).context("Invalid estimate")?)),
+        }
+    }
+
+    fn parse_reminders(&self) -> Result<Vec<Duration>> {
+        self.reminders
+            .split(',')
+            .map(|r| r.trim())
+            .filter(|r| !r.is_empty())
+            .map(utils::parse_duration)
+            .collect::<Result<Vec<_>>>()
+            .context("Invalid reminders")
+    }
+
+    fn parse_links(&self) -> Result<Vec<String>> {
+        let links = utils::parse_links(&self.links);
+        match links.iter().find(|link| !utils::is_link(link)) {
+            Some(link) => Err(anyhow!("'{}' is not a URL, file path or mailto: address", link)),
+            None => Ok(links),
+        }
+    }
+}
+
+/// The fields of a form, to preview them one at a time
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Field {
+    Name,
+    Date,
+    Scheduled,
+    Repeats,
+    Description,
+    Links,
+    Tags,
+    Project,
+    Priority,
+    Estimate,
+    Reminders,
+}
+
+/// Tells the formats a date can be typed in
+fn date_error(field: &str, settings: &Settings) -> String {
+    let formats = &settings.date_formats;
+    format!(
+        "Invalid {}, expected {}, {} or a day like tomorrow, fri or 3d",
+        field, formats.input_date_hint, formats.input_datetime_hint
+    )
 }
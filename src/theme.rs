@@ -0,0 +1,316 @@
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use tui::style::{Color, Modifier, Style};
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum ThemePreset {
+    #[default]
+    Dark,
+    Light,
+    HighContrast,
+}
+
+/// A style in the config, e.g. `{"fg": "blue", "bold": true}`. Colors are names
+/// like `lightred`, 256-color indices like `208` or hex like `#ff8800`
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+pub struct StyleConfig {
+    #[serde(default)]
+    pub fg: Option<String>,
+    #[serde(default)]
+    pub bg: Option<String>,
+    #[serde(default)]
+    pub bold: Option<bool>,
+    #[serde(default)]
+    pub italic: Option<bool>,
+    #[serde(default)]
+    pub underlined: Option<bool>,
+    #[serde(default)]
+    pub reversed: Option<bool>,
+}
+
+/// A preset and the styles to change in it, e.g.
+/// `{"preset": "light", "styles": {"header": {"fg": "magenta"}}}`
+#[derive(Deserialize, Serialize, Debug, Default)]
+pub struct ThemeConfig {
+    #[serde(default)]
+    pub preset: ThemePreset,
+    #[serde(default)]
+    pub styles: BTreeMap<String, StyleConfig>,
+}
+
+/// The styles the TUI draws with
+#[derive(Clone)]
+pub struct Theme {
+    /// Group headers and the scrollbar
+    pub header: Style,
+    /// The current task and selected tabs
+    pub selected: Style,
+    /// Open tasks
+    pub text: Style,
+    /// Complete and cancelled tasks
+    pub complete: Style,
+    pub overdue: Style,
+    /// Open tasks due within the `due_soon` window
+    pub due_soon: Style,
+    pub error: Style,
+    pub border: Style,
+    /// The field being edited
+    pub active: Style,
+    /// Secondary text like effort totals
+    pub muted: Style,
+    /// Search matches
+    pub matched: Style,
+    /// Status bar messages and keys in the help
+    pub message: Style,
+    /// Inline code and code blocks in descriptions
+    pub code: Style,
+}
+
+impl Theme {
+    pub fn preset(preset: ThemePreset) -> Theme {
+        let fg = |color| Style::default().fg(color);
+        let bold = Modifier::BOLD;
+        match preset {
+            ThemePreset::Dark => Theme {
+                header: fg(Color::LightBlue).add_modifier(bold),
+                selected: fg(Color::LightYellow).add_modifier(bold),
+                text: fg(Color::White).add_modifier(bold),
+                complete: fg(Color::DarkGray).add_modifier(bold),
+                overdue: fg(Color::LightRed).add_modifier(bold),
+                due_soon: fg(Color::Yellow).add_modifier(bold),
+                error: fg(Color::Red),
+                border: Style::default(),
+                active: fg(Color::Yellow),
+                muted: fg(Color::DarkGray),
+                matched: fg(Color::LightMagenta).add_modifier(Modifier::UNDERLINED),
+                message: fg(Color::LightYellow),
+                code: fg(Color::LightCyan),
+            },
+            ThemePreset::Light => Theme {
+                header: fg(Color::Blue).add_modifier(bold),
+                selected: fg(Color::Magenta).add_modifier(bold),
+                text: fg(Color::Black).add_modifier(bold),
+                complete: fg(Color::Gray),
+                overdue: fg(Color::Red).add_modifier(bold),
+                due_soon: fg(Color::Indexed(130)).add_modifier(bold),
+                error: fg(Color::Red),
+                border: fg(Color::DarkGray),
+                active: fg(Color::Blue),
+                muted: fg(Color::DarkGray),
+                matched: fg(Color::Red).add_modifier(Modifier::UNDERLINED),
+                message: fg(Color::Blue),
+                code: fg(Color::Cyan),
+            },
+            ThemePreset::HighContrast => Theme {
+                header: fg(Color::White).add_modifier(bold | Modifier::UNDERLINED),
+                selected: Style::default()
+                    .fg(Color::Black)
+                    .bg(Color::Yellow)
+                    .add_modifier(bold),
+                text: fg(Color::White).add_modifier(bold),
+                complete: fg(Color::Gray).add_modifier(Modifier::ITALIC),
+                overdue: fg(Color::Red).add_modifier(bold | Modifier::UNDERLINED),
+                due_soon: fg(Color::Yellow).add_modifier(bold | Modifier::UNDERLINED),
+                error: Style::default()
+                    .fg(Color::White)
+                    .bg(Color::Red)
+                    .add_modifier(bold),
+                border: fg(Color::White),
+                active: fg(Color::Yellow).add_modifier(bold),
+                muted: fg(Color::Gray),
+                matched: fg(Color::Black).bg(Color::Cyan),
+                message: fg(Color::Yellow).add_modifier(bold),
+                code: fg(Color::Black).bg(Color::White),
+            },
+        }
+    }
+
+    /// The preset with the configured styles on top. Colors are dropped when `NO_COLOR` is set
+    pub fn new(config: &ThemeConfig) -> Result<Theme> {
+        let mut theme = Theme::preset(config.preset);
+        for (name, style_config) in config.styles.iter() {
+            let style = theme
+                .style_mut(name)
+                .ok_or_else(|| anyhow!("Unknown style '{}' in theme", name))?;
+            *style = style_config.apply(*style)?;
+        }
+
+        if no_color() {
+            theme.remove_colors();
+        }
+        Ok(theme)
+    }
+
+    fn style_mut(&mut self, name: &str) -> Option<&mut Style> {
+        match name {
+            "header" => Some(&mut self.header),
+            "selected" => Some(&mut self.selected),
+            "text" => Some(&mut self.text),
+            "complete" => Some(&mut self.complete),
+            "overdue" => Some(&mut self.overdue),
+            "due_soon" => Some(&mut self.due_soon),
+            "error" => Some(&mut self.error),
+            "border" => Some(&mut self.border),
+            "active" => Some(&mut self.active),
+            "muted" => Some(&mut self.muted),
+            "matched" => Some(&mut self.matched),
+            "message" => Some(&mut self.message),
+            "code" => Some(&mut self.code),
+            _ => None,
+        }
+    }
+
+    /// Keeps bold, underline and the rest so states stay distinguishable
+    fn remove_colors(&mut self) {
+        for style in [
+            &mut self.header,
+            &mut self.selected,
+            &mut self.text,
+            &mut self.complete,
+            &mut self.overdue,
+            &mut self.due_soon,
+            &mut self.error,
+            &mut self.border,
+            &mut self.active,
+            &mut self.muted,
+            &mut self.matched,
+            &mut self.message,
+            &mut self.code,
+        ] {
+            style.fg = None;
+            style.bg = None;
+        }
+        self.selected = self.selected.add_modifier(Modifier::REVERSED);
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme::preset(ThemePreset::default())
+    }
+}
+
+impl StyleConfig {
+    fn apply(&self, mut style: Style) -> Result<Style> {
+        if let Some(fg) = &self.fg {
+            style.fg = Some(parse_color(fg)?);
+        }
+        if let Some(bg) = &self.bg {
+            style.bg = Some(parse_color(bg)?);
+        }
+
+        let modifiers = [
+            (self.bold, Modifier::BOLD),
+            (self.italic, Modifier::ITALIC),
+            (self.underlined, Modifier::UNDERLINED),
+            (self.reversed, Modifier::REVERSED),
+        ];
+        for (enabled, modifier) in modifiers {
+            style = match enabled {
+                Some(true) => style.add_modifier(modifier),
+                Some(false) => style.remove_modifier(modifier),
+                None => style,
+            };
+        }
+        Ok(style)
+    }
+}
+
+/// Whether colors are turned off, see https://no-color.org
+pub fn no_color() -> bool {
+    std::env::var("NO_COLOR").is_ok_and(|v| !v.is_empty())
+}
+
+/// Wraps text in the ANSI escape codes of a style, for printing to a terminal
+pub fn paint(text: &str, style: Style) -> String {
+    let mut codes = vec![];
+    let modifiers = [
+        (Modifier::BOLD, "1"),
+        (Modifier::DIM, "2"),
+        (Modifier::ITALIC, "3"),
+        (Modifier::UNDERLINED, "4"),
+        (Modifier::REVERSED, "7"),
+    ];
+    for (modifier, code) in modifiers {
+        if style.add_modifier.contains(modifier) {
+            codes.push(code.to_string());
+        }
+    }
+    if let Some(fg) = style.fg {
+        codes.push(color_code(fg, 30));
+    }
+    if let Some(bg) = style.bg {
+        codes.push(color_code(bg, 40));
+    }
+
+    if codes.is_empty() {
+        return text.to_string();
+    }
+    format!("\x1b[{}m{}\x1b[0m", codes.join(";"), text)
+}
+
+/// The SGR code of a color, `base` being 30 for the foreground and 40 for the background
+fn color_code(color: Color, base: u8) -> String {
+    let (offset, bright) = match color {
+        Color::Reset => return (base + 9).to_string(),
+        Color::Indexed(index) => return format!("{};5;{}", base + 8, index),
+        Color::Rgb(r, g, b) => return format!("{};2;{};{};{}", base + 8, r, g, b),
+        Color::Black => (0, false),
+        Color::Red => (1, false),
+        Color::Green => (2, false),
+        Color::Yellow => (3, false),
+        Color::Blue => (4, false),
+        Color::Magenta => (5, false),
+        Color::Cyan => (6, false),
+        Color::Gray => (7, false),
+        Color::DarkGray => (0, true),
+        Color::LightRed => (1, true),
+        Color::LightGreen => (2, true),
+        Color::LightYellow => (3, true),
+        Color::LightBlue => (4, true),
+        Color::LightMagenta => (5, true),
+        Color::LightCyan => (6, true),
+        Color::White => (7, true),
+    };
+    match bright {
+        true => (base + 60 + offset).to_string(),
+        false => (base + offset).to_string(),
+    }
+}
+
+pub fn parse_color(s: &str) -> Result<Color> {
+    let color = s.trim().to_lowercase().replace(['-', '_', ' '], "");
+    let color = match color.as_str() {
+        "reset" | "default" => Color::Reset,
+        "black" => Color::Black,
+        "red" => Color::Red,
+        "green" => Color::Green,
+        "yellow" => Color::Yellow,
+        "blue" => Color::Blue,
+        "magenta" => Color::Magenta,
+        "cyan" => Color::Cyan,
+        "gray" | "grey" => Color::Gray,
+        "darkgray" | "darkgrey" => Color::DarkGray,
+        "lightred" => Color::LightRed,
+        "lightgreen" => Color::LightGreen,
+        "lightyellow" => Color::LightYellow,
+        "lightblue" => Color::LightBlue,
+        "lightmagenta" => Color::LightMagenta,
+        "lightcyan" => Color::LightCyan,
+        "white" => Color::White,
+        hex if hex.starts_with('#') && hex.len() == 7 => {
+            let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16);
+            match (channel(1), channel(3), channel(5)) {
+                (Ok(r), Ok(g), Ok(b)) => Color::Rgb(r, g, b),
+                _ => return Err(anyhow!("Invalid color '{}'", s)),
+            }
+        }
+        index => match index.parse() {
+            Ok(index) => Color::Indexed(index),
+            Err(_) => return Err(anyhow!("Unknown color '{}'", s)),
+        },
+    };
+    Ok(color)
+}
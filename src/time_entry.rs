@@ -0,0 +1,48 @@
+use crate::task::{deserialize_dt, deserialize_opt_dt, serialize_dt, serialize_opt_dt};
+use chrono::{DateTime, Duration, Local};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct TimeEntry {
+    #[serde(serialize_with = "serialize_dt", deserialize_with = "deserialize_dt")]
+    pub start: DateTime<Local>,
+    /// Empty while the timer is running
+    #[serde(
+        default,
+        serialize_with = "serialize_opt_dt",
+        deserialize_with = "deserialize_opt_dt"
+    )]
+    pub end: Option<DateTime<Local>>,
+}
+
+impl TimeEntry {
+    pub fn start_now() -> TimeEntry {
+        TimeEntry {
+            start: Local::now(),
+            end: None,
+        }
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.end.is_none()
+    }
+
+    pub fn end_or_now(&self) -> DateTime<Local> {
+        self.end.unwrap_or_else(Local::now)
+    }
+
+    pub fn duration(&self) -> Duration {
+        self.end_or_now() - self.start
+    }
+
+    /// The part of this entry that falls between `from` and `to`
+    pub fn duration_between(&self, from: DateTime<Local>, to: DateTime<Local>) -> Duration {
+        let start = self.start.max(from);
+        let end = self.end_or_now().min(to);
+        if end > start {
+            end - start
+        } else {
+            Duration::zero()
+        }
+    }
+}
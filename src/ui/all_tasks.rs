@@ -1,37 +1,231 @@
 use crate::app::App;
+use crate::editor;
+use crate::task_form::TaskForm;
+use crate::ui::help;
+use crate::ui::markdown;
+use crate::keybindings::{Action, Keymaps};
+use crate::priority::Priority;
+use crate::query::Query;
 use crate::repeat::Repeat;
+use crate::search;
+use crate::sort::{SortField, SortOrder};
+use crate::status::Status;
 use crate::task::Task;
+use crate::theme::Theme;
 use crate::ui::{Page, UIPage};
 use crate::utils;
 use anyhow::Result;
-use chrono::{DateTime, Local, TimeZone};
-use crossterm::event::{self, Event, KeyCode};
-use itertools::{Group, Itertools};
-use std::cell::RefCell;
+use chrono::{DateTime, Duration, Local};
+use crossterm::event::{self, Event, KeyCode, MouseButton, MouseEvent, MouseEventKind};
+use itertools::Itertools;
+use std::cell::{Cell as StdCell, RefCell};
 use std::rc::Rc;
+use std::time::Instant;
+use unicode_width::UnicodeWidthStr;
 use tui::layout::Direction;
 use tui::text::{Span, Spans};
-use tui::widgets::{Block, Borders, Cell, Paragraph, Row, Table, Wrap};
+use tui::widgets::{Block, Borders, Cell, Clear, Paragraph, Row, Table, TableState, Tabs, Wrap};
 use tui::{
     backend::Backend,
-    layout::{Constraint, Layout},
-    style::{Color, Modifier, Style},
+    layout::{Constraint, Layout, Rect},
+    style::{Modifier, Style},
     Frame, Terminal,
 };
 
+#[derive(PartialEq)]
+pub enum PromptKind {
+    Snooze,
+    Filter,
+    Search,
+    Tags,
+    Priority,
+}
+
+/// A single line input shown under the task list
+pub struct Prompt {
+    pub kind: PromptKind,
+    pub input: String,
+    pub error: Option<String>,
+}
+
+impl Prompt {
+    pub fn new(kind: PromptKind) -> Prompt {
+        Prompt {
+            kind,
+            input: "".to_string(),
+            error: None,
+        }
+    }
+
+    pub fn title(&self) -> String {
+        if let Some(error) = &self.error {
+            return error.clone();
+        }
+        match self.kind {
+            PromptKind::Snooze => "Snooze to (today, tomorrow, mon, 2d, -1d or a date)".to_string(),
+            PromptKind::Filter => "Filter (e.g. due<=+3d and tag:work), empty to clear".to_string(),
+            PromptKind::Search => "Search (Enter to keep, n/N to jump, Esc to clear)".to_string(),
+            PromptKind::Tags => "Tags to add, or remove with a leading - (e.g. work,-later)".to_string(),
+            PromptKind::Priority => "Priority (low, medium, high), empty to clear".to_string(),
+        }
+    }
+}
+
+/// What choosing an item of a `ListPicker` does
+pub enum ListPurpose {
+    OpenLink,
+    /// Checks or unchecks the checklist item on each line of the task's description
+    ToggleItem { task_id: usize, lines: Vec<usize> },
+}
+
+/// A popup to choose a link of a task to open, or a checklist item to toggle
+pub struct ListPicker {
+    pub purpose: ListPurpose,
+    pub items: Vec<String>,
+    pub selected: usize,
+    /// Where the list was drawn, for clicks
+    pub area: StdCell<Rect>,
+}
+
+impl ListPicker {
+    pub fn new(purpose: ListPurpose, items: Vec<String>) -> ListPicker {
+        ListPicker {
+            purpose,
+            items,
+            selected: 0,
+            area: StdCell::new(Rect::default()),
+        }
+    }
+}
+
+/// How long status bar messages stay up
+const MESSAGE_SECONDS: u64 = 5;
+
+/// How many changes can be undone
+pub const UNDO_LIMIT: usize = 20;
+
+/// The sort orders `o` cycles through
+const SORT_PRESETS: [&str; 7] = [
+    "due",
+    "priority,due",
+    "project,due",
+    "tag,due",
+    "status,due",
+    "-created",
+    "name",
+];
+
 pub struct AllTasksPage {
     pub show_hidden: bool,
+    pub show_waiting: bool,
     pub current_idx: Option<usize>,
+    pub prompt: Option<Prompt>,
+    pub list_picker: Option<ListPicker>,
+    pub filter: Option<(String, Query)>,
+    pub search: Option<String>,
+    /// IDs of the tasks marked with space
+    pub marked: Vec<usize>,
+    /// The ID of the task visual mode started on
+    pub visual_start: Option<usize>,
+    pub table_state: RefCell<TableState>,
+    /// The first row shown, kept in step with `table_state` for the scrollbar
+    pub list_offset: StdCell<usize>,
+    /// How many rows fit in the list, for paging
+    pub list_height: StdCell<u16>,
+    /// Where the list was drawn and the task index and height of each row, for clicks
+    pub list_area: StdCell<Rect>,
+    pub list_rows: RefCell<Vec<(Option<usize>, u16)>>,
+    pub keymaps: Rc<Keymaps>,
+    pub theme: Rc<Theme>,
+    pub show_help: bool,
+    /// A message shown in the status bar for a few seconds
+    pub message: Option<(String, Instant)>,
+    /// Snapshots of the tasks before each change, latest last
+    pub undo_stack: Vec<Vec<Task>>,
     pub app: Rc<RefCell<App>>,
 }
 
 impl AllTasksPage {
-    pub fn new(app: Rc<RefCell<App>>) -> AllTasksPage {
+    pub fn new(app: Rc<RefCell<App>>, keymaps: Rc<Keymaps>, theme: Rc<Theme>) -> AllTasksPage {
         let show_hidden = app.borrow().settings.show_complete;
-        AllTasksPage {
+        let show_waiting = app.borrow().settings.show_waiting;
+        let mut page = AllTasksPage {
             show_hidden,
+            show_waiting,
             current_idx: None,
+            prompt: None,
+            list_picker: None,
+            filter: None,
+            search: None,
+            marked: vec![],
+            visual_start: None,
+            table_state: RefCell::new(TableState::default()),
+            list_offset: StdCell::new(0),
+            list_height: StdCell::new(0),
+            list_area: StdCell::new(Rect::default()),
+            list_rows: RefCell::new(vec![]),
+            keymaps,
+            theme,
+            show_help: false,
+            message: None,
+            undo_stack: vec![],
             app,
+        };
+        page.apply_view();
+        page
+    }
+
+    /// Uses the filter and visibility of the active view
+    pub fn apply_view(&mut self) {
+        // A view removed from the config falls back to all tasks
+        let stale = {
+            let settings = &self.app.borrow().settings;
+            settings.context.clone().filter(|_| settings.get_context().is_none())
+        };
+        if let Some(context) = stale {
+            self.app.borrow_mut().settings.set_context(None);
+            self.set_message(format!("View '{}' no longer exists, showing all tasks", context));
+        }
+
+        let app = self.app.borrow();
+        let settings = &app.settings;
+        let view = settings.get_context().cloned().unwrap_or_default();
+        self.show_hidden = view.show_complete.unwrap_or(settings.show_complete);
+        self.show_waiting = view.show_waiting.unwrap_or(settings.show_waiting);
+        self.filter = None;
+
+        let filter = view.filter.clone().unwrap_or_default();
+        match view.get_query(settings) {
+            Ok(query) => self.filter = query.map(|q| (filter, q)),
+            Err(e) => {
+                // Let the filter be fixed in the prompt
+                let mut prompt = Prompt::new(PromptKind::Filter);
+                prompt.input = filter;
+                prompt.error = Some(e.to_string());
+                self.prompt = Some(prompt);
+            }
+        }
+    }
+
+    /// Switches to the nth view in the config, or to no view for 0
+    pub fn switch_view(&mut self, n: usize) {
+        let task_id = self.get_current_task_id();
+        let mut app = self.app.borrow_mut();
+        let context = match n {
+            0 => None,
+            n => match app.settings.views.keys().nth(n - 1) {
+                Some(name) => Some(name.clone()),
+                None => return,
+            },
+        };
+        app.settings.set_context(context);
+        app.sort_tasks();
+        drop(app);
+
+        self.apply_view();
+        match task_id {
+            Some(task_id) => self.select_task(task_id),
+            None => self.move_closest(),
         }
     }
 
@@ -44,115 +238,587 @@ impl AllTasksPage {
         Some(self.app.borrow().tasks[idx].id.unwrap())
     }
 
+    pub fn select_task(&mut self, task_id: usize) {
+        let idx = self
+            .app
+            .borrow()
+            .tasks
+            .iter()
+            .position(|t| t.get_id() == task_id);
+        self.current_idx = idx;
+        self.move_closest();
+    }
+
+    pub fn is_visible(&self, task: &Task) -> bool {
+        if !self.show_hidden && task.status.is_closed() {
+            return false;
+        }
+        if !self.show_waiting && task.is_waiting() {
+            return false;
+        }
+        match &self.filter {
+            Some((_, query)) => query.matches(task),
+            None => true,
+        }
+    }
+
+    fn render_status_bar<B: Backend>(&self, f: &mut Frame<B>, area: Rect, selected: usize) {
+        let app = self.app.borrow();
+        let today = Local::now().date_naive();
+        let open: Vec<&Task> = app.tasks.iter().filter(|t| !t.status.is_closed()).collect();
+        let overdue = open.iter().filter(|t| t.is_overdue()).count();
+        let due_today = open.iter().filter(|t| t.date.date_naive() == today).count();
+
+        let mut summary = format!("{} open", open.len());
+        if overdue > 0 {
+            summary = format!("{} · {} overdue", summary, overdue);
+        }
+        summary = format!("{} · {} due today", summary, due_today);
+        if let Some(context) = &app.settings.context {
+            summary = format!("{} · view {}", summary, context);
+        }
+        if let Some((filter, _)) = &self.filter {
+            summary = format!("{} · filter {}", summary, filter);
+        }
+        let help_keys = self.keymaps.all_tasks.keys_for(Action::Help);
+        summary = format!("{} · {} help ", summary, help_keys);
+
+        let message = match &self.message {
+            Some((message, at)) if at.elapsed().as_secs() < MESSAGE_SECONDS => message.clone(),
+            _ if self.visual_start.is_some() => format!("-- VISUAL -- {} selected", selected),
+            _ if self.has_selection() => format!("{} selected", selected),
+            _ => "".to_string(),
+        };
+
+        let chunks = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Min(0), Constraint::Length(summary.width() as u16)].as_ref())
+            .split(area);
+        let message = Paragraph::new(format!(" {}", message)).style(self.theme.message);
+        let summary_style = if overdue > 0 {
+            self.theme.overdue
+        } else {
+            self.theme.muted
+        };
+        f.render_widget(message, chunks[0]);
+        f.render_widget(Paragraph::new(summary).style(summary_style), chunks[1]);
+    }
+
+    pub fn set_message(&mut self, message: String) {
+        self.message = Some((message, Instant::now()));
+    }
+
+    /// Tells what changed and how to undo it
+    fn set_undo_message(&mut self, count: usize, change: &str) {
+        let tasks = if count == 1 { "Task".to_string() } else { format!("{} tasks", count) };
+        let undo_keys = self.keymaps.all_tasks.keys_for(Action::Undo);
+        self.set_message(format!("{} {} ({} to undo)", tasks, change, undo_keys));
+    }
+
+    /// Remembers the tasks before a change so it can be undone
+    fn checkpoint(&mut self) {
+        let tasks = self.app.borrow().tasks.clone();
+        self.push_undo(tasks);
+    }
+
+    /// Adds the tasks from before a change to the undo history, for changes that can fail
+    fn push_undo(&mut self, tasks: Vec<Task>) {
+        self.undo_stack.push(tasks);
+        if self.undo_stack.len() > UNDO_LIMIT {
+            self.undo_stack.remove(0);
+        }
+    }
+
+    pub fn undo(&mut self) {
+        let tasks = match self.undo_stack.pop() {
+            Some(tasks) => tasks,
+            None => {
+                self.set_message("Nothing to undo".to_string());
+                return;
+            }
+        };
+
+        let task_id = self.get_current_task_id();
+        self.app.borrow_mut().restore_tasks(tasks);
+        match task_id {
+            Some(task_id) => self.select_task(task_id),
+            None => self.move_closest(),
+        }
+        self.set_message("Undone".to_string());
+    }
+
+    /// The IDs of the marked tasks and the ones in the visual range, in list order
+    pub fn get_selection(&self) -> Vec<usize> {
+        let app = self.app.borrow();
+        let visual_range = match (self.visual_start, self.current_idx) {
+            (Some(start_id), Some(current_idx)) => {
+                let start_idx = app
+                    .tasks
+                    .iter()
+                    .position(|t| t.get_id() == start_id)
+                    .unwrap_or(current_idx);
+                Some(start_idx.min(current_idx)..=start_idx.max(current_idx))
+            }
+            _ => None,
+        };
+
+        app.tasks
+            .iter()
+            .enumerate()
+            .filter(|(idx, task)| {
+                let in_visual_range = visual_range.as_ref().is_some_and(|r| r.contains(idx));
+                self.is_visible(task) && (in_visual_range || self.marked.contains(&task.get_id()))
+            })
+            .map(|(_, task)| task.get_id())
+            .collect()
+    }
+
+    /// The tasks actions apply to: the selection if there is one, or else the current task
+    pub fn get_target_ids(&self) -> Vec<usize> {
+        let selection = self.get_selection();
+        if !selection.is_empty() {
+            return selection;
+        }
+        self.get_current_task_id().into_iter().collect()
+    }
+
+    pub fn has_selection(&self) -> bool {
+        self.visual_start.is_some() || !self.marked.is_empty()
+    }
+
+    pub fn clear_selection(&mut self) {
+        self.marked.clear();
+        self.visual_start = None;
+    }
+
+    pub fn toggle_mark(&mut self) {
+        let task_id = match self.get_current_task_id() {
+            Some(task_id) => task_id,
+            None => return,
+        };
+
+        match self.marked.iter().position(|&id| id == task_id) {
+            Some(idx) => {
+                self.marked.remove(idx);
+            }
+            None => self.marked.push(task_id),
+        }
+        self.next();
+    }
+
+    /// Starts visual mode, or keeps its range marked when leaving it
+    pub fn toggle_visual(&mut self) {
+        if self.visual_start.is_some() {
+            self.marked = self.get_selection();
+            self.visual_start = None;
+        } else {
+            self.visual_start = self.get_current_task_id();
+        }
+    }
+
+    /// Completes the targets, or reopens them if they are all complete
     pub fn toggle_selected(&mut self) {
-        if self.current_idx.is_none() {
+        let ids = self.get_target_ids();
+        if ids.is_empty() {
             return;
         }
+        self.toggle_tasks(&ids);
+        self.clear_selection();
+    }
 
-        let task_id = self.get_current_task_id().unwrap();
-        self.app.borrow_mut().toggle_complete_task(task_id);
+    /// Completes the tasks, or reopens them if they're all complete
+    pub fn toggle_tasks(&mut self, ids: &[usize]) {
+        let app = self.app.borrow();
+        let all_complete = ids
+            .iter()
+            .all(|&id| app.get_task(id).is_some_and(|t| t.is_complete()));
+        let status = if all_complete {
+            Status::Todo
+        } else {
+            Status::Complete
+        };
+        drop(app);
 
+        self.checkpoint();
+        self.app.borrow_mut().set_statuses(ids, status);
+        let change = if all_complete { "reopened" } else { "completed" };
+        self.set_undo_message(ids.len(), change);
         if !self.show_hidden {
             self.move_closest();
         }
     }
 
-    pub fn delete_selected(&mut self) {
+    /// Moves the targets to the status after the current task's
+    pub fn cycle_status_selected(&mut self) {
+        let task_id = match self.get_current_task_id() {
+            Some(task_id) => task_id,
+            None => return,
+        };
+
+        let ids = self.get_target_ids();
+        let status = self.app.borrow().get_task(task_id).unwrap().status.next();
+        self.checkpoint();
+        self.app.borrow_mut().set_statuses(&ids, status);
+        let change = format!("marked {}", status.to_string().to_lowercase());
+        self.set_undo_message(ids.len(), &change);
+        self.clear_selection();
+        if !self.show_hidden {
+            self.move_closest();
+        }
+    }
+
+    pub fn toggle_timer_selected(&mut self) {
         if self.current_idx.is_none() {
             return;
         }
 
         let task_id = self.get_current_task_id().unwrap();
-        self.app.borrow_mut().delete_task(task_id);
-        self.move_closest();
+        self.app.borrow_mut().toggle_timer_task(task_id);
     }
 
-    pub fn next(&mut self) {
-        let len = self.app.borrow().tasks.len();
+    pub fn snooze_selected(&mut self, spec: &str) -> Result<()> {
+        let task_id = match self.get_current_task_id() {
+            Some(task_id) => task_id,
+            None => return Ok(()),
+        };
+
+        let ids = self.get_target_ids();
+        let snapshot = self.app.borrow().tasks.clone();
+        self.app.borrow_mut().snooze_tasks(&ids, spec)?;
+        self.push_undo(snapshot);
+        self.set_undo_message(ids.len(), "snoozed");
+        self.clear_selection();
+        self.select_task(task_id);
+        Ok(())
+    }
+
+    /// Adds tags to the targets, or removes the ones starting with `-`
+    pub fn tag_selected(&mut self, spec: &str) {
+        let changes = utils::parse_tags(spec);
+        let ids = self.get_target_ids();
+        self.checkpoint();
+        self.set_undo_message(ids.len(), "retagged");
+        self.app.borrow_mut().update_tasks(&ids, |task| {
+            for change in changes.iter() {
+                match change.strip_prefix('-') {
+                    Some(tag) => task.tags.retain(|t| t != tag),
+                    None if !task.tags.contains(change) => task.tags.push(change.clone()),
+                    None => {}
+                }
+            }
+        });
+        self.clear_selection();
+    }
 
-        if self.current_idx.is_none() && len > 0 {
-            self.current_idx = Some(0);
+    pub fn prioritize_selected(&mut self, spec: &str) -> Result<()> {
+        let priority = Priority::parse_from_str(spec)?;
+        let task_id = self.get_current_task_id();
+        let ids = self.get_target_ids();
+        self.checkpoint();
+        self.app
+            .borrow_mut()
+            .update_tasks(&ids, |task| task.set_priority(priority));
+        self.set_undo_message(ids.len(), "reprioritized");
+        self.clear_selection();
+        if let Some(task_id) = task_id {
+            self.select_task(task_id);
+        }
+        Ok(())
+    }
+
+    pub fn snooze_overdue(&mut self) {
+        let task_id = self.get_current_task_id();
+        let ids = self.app.borrow().overdue_task_ids();
+        if ids.is_empty() {
+            self.set_message("No overdue tasks".to_string());
             return;
-        } else if self.current_idx.is_none() {
+        }
+
+        let snapshot = self.app.borrow().tasks.clone();
+        let snoozed = self.app.borrow_mut().snooze_tasks(&ids, "today");
+        if let Err(e) = snoozed {
+            self.set_message(e.to_string());
             return;
         }
+        self.push_undo(snapshot);
+        self.set_undo_message(ids.len(), "moved to today");
 
-        let curr_idx = self.current_idx.unwrap();
+        if let Some(task_id) = task_id {
+            self.select_task(task_id);
+        }
+    }
 
-        if self.show_hidden && curr_idx + 1 < len {
-            self.current_idx = Some(curr_idx + 1);
-            return;
-        } else if self.show_hidden {
+    pub fn set_filter(&mut self, filter: &str) -> Result<()> {
+        if filter.trim().is_empty() {
+            self.filter = None;
+        } else {
+            let query = Query::parse(filter, &self.app.borrow().settings)?;
+            self.filter = Some((filter.to_string(), query));
+        }
+        self.move_closest();
+        Ok(())
+    }
+
+    fn matches_search(&self, task: &Task) -> bool {
+        match &self.search {
+            Some(query) => self.is_visible(task) && search::score_task(query, task).is_some(),
+            None => false,
+        }
+    }
+
+    /// Moves to the next (or previous) task matching the search, wrapping around
+    pub fn jump_to_match(&mut self, forward: bool, include_current: bool) {
+        let len = self.app.borrow().tasks.len();
+        if len == 0 {
             return;
         }
 
-        for i in curr_idx + 1..len {
-            if !self.app.borrow().tasks[i].complete {
-                self.current_idx = Some(i);
+        let start = match (self.current_idx, forward) {
+            (Some(idx), _) => idx,
+            (None, true) => len - 1,
+            (None, false) => 0,
+        };
+        let first_offset = if include_current { 0 } else { 1 };
+        for offset in first_offset..=len {
+            let idx = if forward {
+                (start + offset) % len
+            } else {
+                (start + len - offset % len) % len
+            };
+            if self.matches_search(&self.app.borrow().tasks[idx]) {
+                self.current_idx = Some(idx);
                 return;
             }
         }
     }
 
-    pub fn prev(&mut self) {
-        let len = self.app.borrow().tasks.len();
-
-        if self.current_idx.is_none() && len > 0 {
-            self.current_idx = Some(len - 1);
-            return;
-        } else if self.current_idx.is_none() {
+    pub fn set_search(&mut self, query: &str) {
+        if query.trim().is_empty() {
+            self.search = None;
             return;
         }
+        self.search = Some(query.to_string());
+        self.jump_to_match(true, true);
+    }
+
+    /// Splits a task name into spans, highlighting the characters matching the search
+    fn name_spans(&self, name: &str, style: Style) -> Vec<Span<'static>> {
+        let positions = match &self.search {
+            Some(query) => match search::fuzzy_match(query, name) {
+                Some(m) => m.positions,
+                None => vec![],
+            },
+            None => vec![],
+        };
+        let highlight_style = style.patch(self.theme.matched);
+
+        name.chars()
+            .enumerate()
+            .group_by(|(idx, _)| positions.contains(idx))
+            .into_iter()
+            .map(|(highlighted, chars)| {
+                let text: String = chars.map(|(_, c)| c).collect();
+                let style = if highlighted { highlight_style } else { style };
+                Span::styled(text, style)
+            })
+            .collect()
+    }
+
+    pub fn submit_prompt(&mut self) {
+        let prompt = match self.prompt.take() {
+            Some(prompt) => prompt,
+            None => return,
+        };
+
+        let result = match prompt.kind {
+            PromptKind::Snooze => self.snooze_selected(&prompt.input),
+            PromptKind::Filter => self.set_filter(&prompt.input),
+            PromptKind::Search => Ok(()),
+            PromptKind::Tags => {
+                self.tag_selected(&prompt.input);
+                Ok(())
+            }
+            PromptKind::Priority => self.prioritize_selected(&prompt.input),
+        };
+
+        // Keep the prompt open so the input can be fixed
+        if let Err(e) = result {
+            self.prompt = Some(Prompt {
+                error: Some(e.to_string()),
+                ..prompt
+            });
+        }
+    }
+
+    fn handle_prompt_key(&mut self, key: KeyCode) {
+        let prompt = self.prompt.as_mut().unwrap();
+        match key {
+            KeyCode::Esc => {
+                if prompt.kind == PromptKind::Search {
+                    self.search = None;
+                }
+                self.prompt = None;
+                return;
+            }
+            KeyCode::Enter => {
+                self.submit_prompt();
+                return;
+            }
+            KeyCode::Char(c) => prompt.input.push(c),
+            KeyCode::Backspace => {
+                prompt.input.pop();
+            }
+            _ => {}
+        }
 
-        let curr_idx = self.current_idx.unwrap();
+        // Search as you type
+        if prompt.kind == PromptKind::Search {
+            let query = prompt.input.clone();
+            self.set_search(&query);
+        }
+    }
 
-        if self.show_hidden && curr_idx > 0 {
-            self.current_idx = Some((curr_idx + len - 1) % len);
+    pub fn delete_selected(&mut self) {
+        let ids = self.get_target_ids();
+        if ids.is_empty() {
             return;
         }
 
-        for i in (0..curr_idx).rev() {
-            if !self.app.borrow().tasks[i].complete {
+        self.checkpoint();
+        self.app.borrow_mut().delete_tasks(&ids);
+        self.set_undo_message(ids.len(), "deleted");
+        self.clear_selection();
+        self.move_closest();
+    }
+
+    pub fn next(&mut self) {
+        let len = self.app.borrow().tasks.len();
+
+        let start = match self.current_idx {
+            Some(curr_idx) => curr_idx + 1,
+            None => 0,
+        };
+
+        for i in start..len {
+            if self.is_visible(&self.app.borrow().tasks[i]) {
                 self.current_idx = Some(i);
                 return;
             }
         }
     }
 
-    pub fn groups(&self) -> Vec<Vec<Task>> {
+    pub fn prev(&mut self) {
+        let len = self.app.borrow().tasks.len();
+
+        let end = match self.current_idx {
+            Some(curr_idx) => curr_idx,
+            None => len,
+        };
+
+        for i in (0..end).rev() {
+            if self.is_visible(&self.app.borrow().tasks[i]) {
+                self.current_idx = Some(i);
+                return;
+            }
+        }
+    }
+
+    /// Moves the selection a page down, or up for a negative `pages`
+    pub fn move_pages(&mut self, pages: isize) {
+        let page_size = self.list_height.get().saturating_sub(1).max(1) as usize;
+        for _ in 0..page_size {
+            if pages > 0 {
+                self.next();
+            } else {
+                self.prev();
+            }
+        }
+    }
+
+    pub fn select_first(&mut self) {
+        self.current_idx = None;
+        self.next();
+    }
+
+    pub fn select_last(&mut self) {
+        self.current_idx = None;
+        self.prev();
+    }
+
+    /// The header of the group a task is listed under, following the first sort key
+    pub fn group_title(&self, task: &Task) -> String {
+        let sort_field = self.app.borrow().settings.get_sort_order().primary();
+        let title = match sort_field {
+            SortField::Due if task.is_overdue() => "Overdue".to_string(),
+            // Closed tasks aren't overdue, however long ago they were due
+            SortField::Due if task.date.date_naive() < Local::now().date_naive() => {
+                "Past".to_string()
+            }
+            SortField::Due => self.relative_day_str(&task.date),
+            SortField::Scheduled => match &task.scheduled {
+                Some(scheduled) => format!("Scheduled {}", self.relative_day_str(scheduled)),
+                None => "Not scheduled".to_string(),
+            },
+            SortField::Created => match &task.created {
+                Some(created) => format!("Created {}", self.relative_day_str(created)),
+                None => "Created earlier".to_string(),
+            },
+            SortField::Priority => match &task.priority {
+                Some(priority) => format!("{} priority", priority),
+                None => "No priority".to_string(),
+            },
+            SortField::Project => task.project.clone().unwrap_or("No project".to_string()),
+            SortField::Tag => task.tags.first().cloned().unwrap_or("Untagged".to_string()),
+            SortField::Status => task.status.to_string(),
+            SortField::Name | SortField::Estimate | SortField::Id => "All tasks".to_string(),
+        };
+        title.to_uppercase()
+    }
+
+    pub fn groups(&self) -> Vec<(String, Vec<Task>)> {
         self.app
             .borrow()
             .tasks
             .clone()
             .into_iter()
-            .group_by(|t| t.date.date_naive())
+            .group_by(|t| self.group_title(t))
             .into_iter()
-            .map(|(_, group)| group.collect())
+            .map(|(title, group)| (title, group.collect()))
             .collect()
     }
 
-    pub fn move_closest(&mut self) {
-        let len = self.app.borrow().tasks.len();
+    pub fn cycle_sort(&mut self) {
+        let task_id = self.get_current_task_id();
+        let mut app = self.app.borrow_mut();
+        let current = app.settings.get_sort_order().to_string();
+        let next = match SORT_PRESETS.iter().position(|s| *s == current) {
+            Some(idx) => SORT_PRESETS[(idx + 1) % SORT_PRESETS.len()],
+            None => SORT_PRESETS[0],
+        };
+        let sort = SortOrder::parse_from_str(next).unwrap();
+        app.settings.set_sort(&sort);
+        app.sort_tasks();
+        drop(app);
 
-        if self.current_idx.is_none() && len > 0 {
-            self.current_idx = Some(0);
-            return;
-        } else if self.current_idx.is_none() {
-            return;
+        if let Some(task_id) = task_id {
+            self.select_task(task_id);
         }
+    }
 
-        let curr_idx = self.current_idx.unwrap();
+    pub fn move_closest(&mut self) {
+        let len = self.app.borrow().tasks.len();
+        let curr_idx = self.current_idx.unwrap_or(0);
         let app = self.app.borrow();
 
         for i in curr_idx..len {
-            if !app.tasks[i].complete {
+            if self.is_visible(&app.tasks[i]) {
                 self.current_idx = Some(i);
                 return;
             }
         }
 
-        for i in (0..curr_idx).rev() {
-            if !app.tasks[i].complete {
+        for i in (0..curr_idx.min(len)).rev() {
+            if self.is_visible(&app.tasks[i]) {
                 self.current_idx = Some(i);
                 return;
             }
@@ -172,14 +838,25 @@ impl AllTasksPage {
         }
     }
 
-    pub fn get_complete_icon(&self, complete: bool) -> String {
-        self.app.borrow().settings.icons.get_complete_icon(complete)
+    pub fn toggle_waiting(&mut self) {
+        self.show_waiting = !self.show_waiting;
+        self.app
+            .borrow_mut()
+            .settings
+            .set_show_waiting(self.show_waiting);
+        if !self.show_waiting {
+            self.move_closest();
+        }
+    }
+
+    pub fn get_status_icon(&self, status: Status) -> String {
+        self.app.borrow().settings.icons.get_status_icon(status)
     }
 
     pub fn get_repeats_icon(&self, repeats: &Repeat) -> String {
         match repeats {
             Repeat::Never => String::from(""),
-            _ => self.app.borrow().settings.icons.repeats.clone(),
+            _ => self.app.borrow().settings.icons.repeats(),
         }
     }
 
@@ -187,24 +864,261 @@ impl AllTasksPage {
         utils::date_to_display_str(date, &self.app.borrow().settings)
     }
 
-    pub fn open_selected_link(&self) {
-        if self.current_idx.is_none() {
+    /// Formats only the day of a date, relative to today when close
+    pub fn relative_day_str(&self, date: &DateTime<Local>) -> String {
+        utils::relative_day_str(date, &self.app.borrow().settings)
+    }
+
+    /// Edits the current task in $EDITOR, as TOML or only its description
+    fn edit_in_editor<B: Backend>(
+        &mut self,
+        terminal: &mut Terminal<B>,
+        description_only: bool,
+    ) -> Result<()> {
+        let task_id = match self.get_current_task_id() {
+            Some(task_id) => task_id,
+            None => return Ok(()),
+        };
+        let task = self.app.borrow().get_task(task_id).unwrap().clone();
+
+        let app = self.app.borrow();
+        let settings = &app.settings;
+        let edited = super::suspend(terminal, settings.mouse, || {
+            if description_only {
+                let description = task.description.clone().unwrap_or_default();
+                let description = editor::edit_text(&description, "md")?;
+                let mut edited = task.clone();
+                edited.set_description(description.trim_end().to_string());
+                Ok(Some(edited))
+            } else {
+                let task_form = TaskForm::from_task(&task, settings);
+                editor::edit_form(&task_form, |task_form| {
+                    task_form.submit_onto(task.clone(), settings)
+                })
+            }
+        })?;
+        drop(app);
+
+        match edited {
+            Ok(Some(edited)) => {
+                self.checkpoint();
+                self.app.borrow_mut().update_task(edited);
+                self.set_undo_message(1, "edited");
+                self.select_task(task_id);
+            }
+            Ok(None) => self.set_message("Edit cancelled".to_string()),
+            Err(e) => self.set_message(e.to_string()),
+        }
+        Ok(())
+    }
+
+    /// Toggles the checklist item of the current task, or asks which one when it has several
+    fn check_item(&mut self) {
+        let task_id = match self.get_current_task_id() {
+            Some(task_id) => task_id,
+            None => return,
+        };
+        let description = self.app.borrow().get_task(task_id).unwrap().description.clone();
+        let checklist = markdown::checklist(&description.unwrap_or_default());
+        match checklist.len() {
+            0 => self.set_message("The task has no checklist".to_string()),
+            1 => self.toggle_item(task_id, checklist[0].0),
+            _ => {
+                let status_icon = |checked| match checked {
+                    true => self.get_status_icon(Status::Complete),
+                    false => self.get_status_icon(Status::Todo),
+                };
+                let items = checklist
+                    .iter()
+                    .map(|(_, checked, item)| format!("{} {}", status_icon(*checked).trim(), item))
+                    .collect();
+                let lines = checklist.iter().map(|(line_idx, _, _)| *line_idx).collect();
+                let mut picker = ListPicker::new(ListPurpose::ToggleItem { task_id, lines }, items);
+                picker.selected = checklist.iter().position(|(_, checked, _)| !checked).unwrap_or(0);
+                self.list_picker = Some(picker);
+            }
+        }
+    }
+
+    /// Checks or unchecks the checklist item on a line of a task's description
+    fn toggle_item(&mut self, task_id: usize, line_idx: usize) {
+        let mut task = self.app.borrow().get_task(task_id).unwrap().clone();
+        let description = task.description.clone().unwrap_or_default();
+        let description = markdown::toggle_checkbox(&description, line_idx);
+        let checklist = markdown::checklist(&description);
+        let (position, checked) = match checklist.iter().position(|(idx, _, _)| *idx == line_idx) {
+            Some(position) => (position + 1, checklist[position].1),
+            None => return,
+        };
+
+        self.checkpoint();
+        task.set_description(description);
+        self.app.borrow_mut().update_task(task);
+        let action = if checked { "Checked" } else { "Unchecked" };
+        self.set_message(format!("{} item {} of {}", action, position, checklist.len()));
+        self.select_task(task_id);
+    }
+
+    /// Clicks select a task or toggle it on its checkbox, the wheel moves through tasks
+    fn handle_mouse(&mut self, mouse: MouseEvent) {
+        let clicked = mouse.kind == MouseEventKind::Down(MouseButton::Left);
+        if self.show_help {
+            self.show_help = !clicked;
+            return;
+        }
+        if self.prompt.is_some() {
+            return;
+        }
+        if let Some(picker) = &mut self.list_picker {
+            let area = picker.area.get();
+            match mouse.kind {
+                MouseEventKind::ScrollDown => self.handle_list_picker_key(KeyCode::Down),
+                MouseEventKind::ScrollUp => self.handle_list_picker_key(KeyCode::Up),
+                _ if clicked => {
+                    let row = mouse.row as usize;
+                    let first = area.y as usize + 1;
+                    if (first..first + picker.items.len()).contains(&row) {
+                        picker.selected = row - first;
+                        self.handle_list_picker_key(KeyCode::Enter);
+                    } else {
+                        self.list_picker = None;
+                    }
+                }
+                _ => {}
+            }
             return;
         }
 
-        let task_id = self.get_current_task_id().unwrap();
-        let desc_text = self
-            .app
-            .borrow()
-            .get_task(task_id)
-            .unwrap()
-            .description
-            .clone()
-            .unwrap_or_default();
-        if utils::is_hyperlink(&desc_text) {
-            open::that(desc_text).unwrap();
+        match mouse.kind {
+            MouseEventKind::ScrollDown => self.next(),
+            MouseEventKind::ScrollUp => self.prev(),
+            _ if clicked => {
+                let idx = match self.task_at(mouse.column, mouse.row) {
+                    Some(idx) => idx,
+                    None => return,
+                };
+                self.current_idx = Some(idx);
+
+                let status = self.app.borrow().tasks[idx].status;
+                let checkbox_width = self.get_status_icon(status).width() as u16;
+                if mouse.column <= self.list_area.get().x + checkbox_width {
+                    let task_id = self.get_current_task_id().unwrap();
+                    self.toggle_tasks(&[task_id]);
+                }
+            }
+            _ => {}
         }
     }
+
+    /// The index of the task drawn at a position in the list
+    fn task_at(&self, column: u16, row: u16) -> Option<usize> {
+        let area = self.list_area.get();
+        let inside = column > area.x
+            && column + 1 < area.x + area.width
+            && row > area.y
+            && row + 1 < area.y + area.height;
+        if !inside {
+            return None;
+        }
+
+        // Rows are one line, the rest of their height is the margin under groups
+        let mut y = area.y + 1;
+        for &(idx, height) in self.list_rows.borrow().iter().skip(self.list_offset.get()) {
+            if row == y {
+                return idx;
+            }
+            y += height;
+            if y > row {
+                return None;
+            }
+        }
+        None
+    }
+
+    /// Opens the link of the current task, or asks which one when it has several
+    pub fn open_selected_link(&mut self) {
+        let task_id = match self.get_current_task_id() {
+            Some(task_id) => task_id,
+            None => return,
+        };
+        let links = self.app.borrow().get_task(task_id).unwrap().all_links();
+        match links.len() {
+            0 => self.set_message("The task has no links".to_string()),
+            1 => self.open_link(&links[0]),
+            _ => self.list_picker = Some(ListPicker::new(ListPurpose::OpenLink, links)),
+        }
+    }
+
+    fn open_link(&mut self, link: &str) {
+        match utils::open_link(link) {
+            Ok(()) => self.set_message(format!("Opened {}", link)),
+            Err(e) => self.set_message(e.to_string()),
+        }
+    }
+
+    /// Moves through the items, picks one with Enter or its number, closes with Esc
+    fn handle_list_picker_key(&mut self, key: KeyCode) {
+        let picker = self.list_picker.as_mut().unwrap();
+        let last = picker.items.len() - 1;
+        let chosen = match key {
+            KeyCode::Esc | KeyCode::Char('q') => None,
+            KeyCode::Down | KeyCode::Char('j') => {
+                picker.selected = (picker.selected + 1).min(last);
+                return;
+            }
+            KeyCode::Up | KeyCode::Char('k') => {
+                picker.selected = picker.selected.saturating_sub(1);
+                return;
+            }
+            KeyCode::Enter => Some(picker.selected),
+            KeyCode::Char(c) => match c.to_digit(10) {
+                Some(n) if n >= 1 && n as usize <= last + 1 => Some(n as usize - 1),
+                _ => return,
+            },
+            _ => return,
+        };
+
+        let picker = self.list_picker.take().unwrap();
+        match (chosen, picker.purpose) {
+            (Some(idx), ListPurpose::OpenLink) => self.open_link(&picker.items[idx]),
+            (Some(idx), ListPurpose::ToggleItem { task_id, lines }) => {
+                self.toggle_item(task_id, lines[idx])
+            }
+            (None, _) => {}
+        }
+    }
+
+    fn render_list_picker<B: Backend>(&self, f: &mut Frame<B>, picker: &ListPicker) {
+        let items: Vec<Spans> = picker
+            .items
+            .iter()
+            .enumerate()
+            .map(|(idx, item)| {
+                let style = match idx == picker.selected {
+                    true => self.theme.selected,
+                    false => self.theme.text,
+                };
+                Spans::from(vec![
+                    Span::styled(format!("{} ", idx + 1), self.theme.message),
+                    Span::styled(item.clone(), style),
+                ])
+            })
+            .collect();
+
+        let area = help::centered_rect(f.size(), 70, items.len() as u16 + 2);
+        picker.area.set(area);
+        let list = Paragraph::new(items).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(self.theme.border)
+                .title(match picker.purpose {
+                    ListPurpose::OpenLink => "Open which link? (Enter or 1-9, Esc to close)",
+                    ListPurpose::ToggleItem { .. } => "Toggle which item? (Enter or 1-9, Esc to close)",
+                }),
+        );
+        f.render_widget(Clear, area);
+        f.render_widget(list, area);
+    }
 }
 
 impl<B> Page<B> for AllTasksPage
@@ -214,21 +1128,108 @@ where
     fn render(&mut self, terminal: &mut Terminal<B>) -> Result<UIPage> {
         terminal.draw(|f| self.ui(f))?;
 
-        if let Event::Key(key) = event::read()? {
-            match key.code {
-                KeyCode::Char('q') => return Ok(UIPage::Quit),
-                KeyCode::Char('j') => self.next(),
-                KeyCode::Char('k') => self.prev(),
-                KeyCode::Char('x') => self.toggle_selected(),
-                KeyCode::Char('h') => self.toggle_hidden(),
-                KeyCode::Char('d') => self.delete_selected(),
-                KeyCode::Enter => self.open_selected_link(),
-                KeyCode::Char('n') => return Ok(UIPage::NewTask),
-                KeyCode::Char('e') => {
+        // Redraw every second so running timers keep ticking
+        if !event::poll(std::time::Duration::from_secs(1))? {
+            return Ok(UIPage::SamePage);
+        }
+
+        let event = event::read()?;
+        if let Event::Mouse(mouse) = event {
+            self.handle_mouse(mouse);
+            return Ok(UIPage::SamePage);
+        }
+        if let (Event::Paste(text), Some(prompt)) = (&event, &mut self.prompt) {
+            prompt.input.push_str(text.lines().next().unwrap_or(""));
+            return Ok(UIPage::SamePage);
+        }
+
+        if let Event::Key(key) = event {
+            // Any key closes the help
+            if self.show_help {
+                self.show_help = false;
+                return Ok(UIPage::SamePage);
+            }
+
+            if self.prompt.is_some() {
+                self.handle_prompt_key(key.code);
+                return Ok(UIPage::SamePage);
+            }
+
+            if self.list_picker.is_some() {
+                self.handle_list_picker_key(key.code);
+                return Ok(UIPage::SamePage);
+            }
+
+            let action = self.keymaps.all_tasks.action_for(&key, self.search.is_some());
+            match action {
+                Some(Action::Quit) => return Ok(UIPage::Quit),
+                Some(Action::Down) => self.next(),
+                Some(Action::Up) => self.prev(),
+                Some(Action::PageDown) => self.move_pages(1),
+                Some(Action::PageUp) => self.move_pages(-1),
+                Some(Action::First) => self.select_first(),
+                Some(Action::Last) => self.select_last(),
+                Some(Action::Complete) => self.toggle_selected(),
+                Some(Action::CycleStatus) => self.cycle_status_selected(),
+                Some(Action::Timer) => self.toggle_timer_selected(),
+                Some(Action::ShowComplete) => self.toggle_hidden(),
+                Some(Action::ShowWaiting) => self.toggle_waiting(),
+                Some(Action::Delete) => self.delete_selected(),
+                Some(Action::SnoozeDay) => {
+                    if let Err(e) = self.snooze_selected("1d") {
+                        self.set_message(e.to_string());
+                    }
+                }
+                Some(Action::UnsnoozeDay) => {
+                    if let Err(e) = self.snooze_selected("-1d") {
+                        self.set_message(e.to_string());
+                    }
+                }
+                Some(Action::Snooze) if self.current_idx.is_some() => {
+                    self.prompt = Some(Prompt::new(PromptKind::Snooze));
+                }
+                Some(Action::SnoozeOverdue) => self.snooze_overdue(),
+                Some(Action::Sort) => self.cycle_sort(),
+                Some(Action::Filter) => {
+                    let mut prompt = Prompt::new(PromptKind::Filter);
+                    if let Some((filter, _)) = &self.filter {
+                        prompt.input = filter.clone();
+                    }
+                    self.prompt = Some(prompt);
+                }
+                Some(Action::OpenLink) => self.open_selected_link(),
+                Some(Action::Search) => self.prompt = Some(Prompt::new(PromptKind::Search)),
+                Some(Action::NextMatch) => self.jump_to_match(true, false),
+                Some(Action::PrevMatch) => self.jump_to_match(false, false),
+                Some(Action::Visual) => self.toggle_visual(),
+                Some(Action::Mark) => self.toggle_mark(),
+                Some(Action::Tags) if self.current_idx.is_some() => {
+                    self.prompt = Some(Prompt::new(PromptKind::Tags));
+                }
+                Some(Action::Priority) if self.current_idx.is_some() => {
+                    self.prompt = Some(Prompt::new(PromptKind::Priority));
+                }
+                Some(Action::Clear) if self.has_selection() => self.clear_selection(),
+                Some(Action::Clear) => self.search = None,
+                Some(Action::NewTask) => return Ok(UIPage::NewTask),
+                Some(Action::Edit) if self.current_idx.is_some() => {
                     let task_id = self.get_current_task_id().unwrap();
                     return Ok(UIPage::EditTask(task_id));
                 }
-                _ => {}
+                Some(Action::EditDescription) => self.edit_in_editor(terminal, true)?,
+                Some(Action::EditInEditor) => self.edit_in_editor(terminal, false)?,
+                Some(Action::CheckItem) => self.check_item(),
+                Some(Action::Undo) => self.undo(),
+                Some(Action::Help) => self.show_help = true,
+                Some(_) => {}
+                // Number keys switch views
+                None => {
+                    if let KeyCode::Char(c) = key.code {
+                        if let Some(n) = c.to_digit(10) {
+                            self.switch_view(n as usize);
+                        }
+                    }
+                }
             }
         }
 
@@ -236,6 +1237,68 @@ where
     }
 
     fn ui(&self, f: &mut Frame<B>) {
+        let selection = self.get_selection();
+
+        // Status bar at the bottom
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(0), Constraint::Length(1)].as_ref())
+            .split(f.size());
+        let mut area = chunks[0];
+        self.render_status_bar(f, chunks[1], selection.len());
+
+        // Leave room for the prompt at the bottom
+        if let Some(prompt) = &self.prompt {
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Min(0), Constraint::Length(3)].as_ref())
+                .split(area);
+            area = chunks[0];
+
+            let title_style = match prompt.error {
+                Some(_) => self.theme.error,
+                None => Style::default(),
+            };
+            let input = Paragraph::new(prompt.input.as_ref()).block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(self.theme.border)
+                    .title(Span::styled(prompt.title(), title_style)),
+            );
+            f.render_widget(input, chunks[1]);
+            f.set_cursor(
+                chunks[1].x + prompt.input.width() as u16 + 1,
+                chunks[1].y + 1,
+            );
+        }
+
+        // View tabs at the top
+        let app = self.app.borrow();
+        if !app.settings.views.is_empty() {
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Length(1), Constraint::Min(0)].as_ref())
+                .split(area);
+            area = chunks[1];
+
+            let mut titles = vec![Spans::from(" 0 All")];
+            for (idx, name) in app.settings.views.keys().enumerate() {
+                titles.push(Spans::from(format!("{} {}", idx + 1, name)));
+            }
+            let selected = match &app.settings.context {
+                Some(context) => {
+                    let idx = app.settings.views.keys().position(|k| k == context);
+                    idx.map_or(0, |i| i + 1)
+                }
+                None => 0,
+            };
+            let tabs = Tabs::new(titles)
+                .select(selected)
+                .highlight_style(self.theme.selected);
+            f.render_widget(tabs, chunks[0]);
+        }
+        drop(app);
+
         let constraints = match self.current_idx {
             Some(_) => [Constraint::Percentage(50), Constraint::Percentage(50)].as_ref(),
             None => [Constraint::Percentage(100)].as_ref(),
@@ -243,70 +1306,122 @@ where
         let chunks = Layout::default()
             .direction(Direction::Horizontal)
             .constraints(constraints)
-            .split(f.size());
+            .split(area);
 
         // Build list
         let mut rows = vec![];
+        let mut row_heights: Vec<u16> = vec![];
+        let mut row_tasks: Vec<Option<usize>> = vec![];
+        let mut selected_row = None;
         let mut current_idx = 0;
-        for group in self.groups() {
+        let group_by_day = self.app.borrow().settings.get_sort_order().primary() == SortField::Due;
+        for (title, group) in self.groups() {
             // Group title
-            let group_date = &group[0].date.date_naive().and_hms_opt(23, 59, 59).unwrap();
-            let group_date = Local.from_local_datetime(group_date).unwrap();
-            let date_str = self.date_to_str(&group_date).to_uppercase();
-            let group_title = " ".to_string() + date_str.as_str();
-            let cell = Cell::from(Span::styled(
-                group_title,
-                Style::default()
-                    .add_modifier(Modifier::BOLD)
-                    .fg(Color::LightBlue),
-            ));
+            let group_title = " ".to_string() + title.as_str();
+            let mut title_spans = vec![Span::styled(group_title, self.theme.header)];
+
+            // Estimated effort for the day. Overdue tasks span several days
+            let effort = utils::total_estimate(group.iter());
+            let is_day = group_by_day && group[0].date.date_naive() >= Local::now().date_naive();
+            if is_day && effort > Duration::zero() {
+                let (effort, over_capacity) =
+                    utils::effort_summary(&effort, &self.app.borrow().settings);
+                let effort_style = if over_capacity {
+                    self.theme.error.add_modifier(Modifier::BOLD)
+                } else {
+                    self.theme.muted
+                };
+                let warning = if over_capacity { " over capacity" } else { "" };
+                let effort_text = format!("  {}{}", effort, warning);
+                title_spans.push(Span::styled(effort_text, effort_style));
+            }
+            let cell = Cell::from(Spans::from(title_spans));
             rows.push(Row::new(vec![cell]));
+            row_heights.push(1);
+            row_tasks.push(None);
             let pre_count = rows.len();
 
             // All tasks in group
             for (idx, item) in group.iter().enumerate() {
                 // Skip if hidden
-                if !self.show_hidden && item.complete {
+                if !self.is_visible(item) {
                     current_idx += 1;
                     continue;
                 }
 
                 // Create string
-                let complete_icon = self.get_complete_icon(item.complete);
+                let status_icon = self.get_status_icon(item.status);
                 let recurring_icon = self.get_repeats_icon(&item.repeats);
-                let title = format!("{} {} {} ", complete_icon, item.name, recurring_icon);
-                let title_style = match (item.complete, self.current_idx) {
-                    (_, Some(idx)) if idx == current_idx => Style::default()
-                        .fg(Color::LightYellow)
-                        .add_modifier(Modifier::BOLD),
-                    (true, _) => Style::default().fg(Color::DarkGray),
-                    _ => Style::default().fg(Color::White),
+                let mut suffix = format!(" {} ", recurring_icon);
+                if item.is_timer_running() {
+                    let timer_icon = self.app.borrow().settings.icons.timer();
+                    let timer = utils::format_timer(&item.total_time());
+                    suffix = format!("{}{} {} ", suffix, timer_icon, timer);
+                }
+                let mut title_style = match (item.status.is_closed(), self.current_idx) {
+                    (_, Some(idx)) if idx == current_idx => self.theme.selected,
+                    (true, _) => self.theme.complete,
+                    _ if item.is_overdue() => self.theme.overdue,
+                    _ if self.app.borrow().settings.is_due_soon(item) => self.theme.due_soon,
+                    _ => self.theme.text,
                 };
-                let title_style = title_style.add_modifier(Modifier::BOLD);
-                let title_cell = Spans::from(Span::styled(title, title_style));
+                if selection.contains(&item.get_id()) {
+                    title_style = title_style.add_modifier(Modifier::REVERSED);
+                }
+                let mut title = vec![Span::styled(format!("{} ", status_icon), title_style)];
+                title.extend(self.name_spans(&item.name, title_style));
+                title.push(Span::styled(suffix, title_style));
+                let title_cell = Spans::from(title);
 
                 // Create row
                 let cell = Cell::from(title_cell);
                 let mut new_row = Row::new(vec![cell]);
 
                 // Add bottom margin if last item in group
+                let mut height = 1;
                 if idx == group.len() - 1 {
                     new_row = new_row.bottom_margin(1);
+                    height += 1;
                 }
 
-                current_idx += 1;
+                if self.current_idx == Some(current_idx) {
+                    selected_row = Some(rows.len());
+                }
                 rows.push(new_row);
+                row_heights.push(height);
+                row_tasks.push(Some(current_idx));
+                current_idx += 1;
             }
 
             // If no tasks in group, pop the group title
             if rows.len() == pre_count {
                 rows.pop();
+                row_heights.pop();
+                row_tasks.pop();
             }
         }
+        let sort = self.app.borrow().settings.get_sort_order().to_string();
+        let list_title = match &self.filter {
+            Some((filter, _)) => format!("Todos by {} ({})", sort, filter),
+            None => format!("Todos by {}", sort),
+        };
         let list = Table::new(rows)
-            .block(Block::default().borders(Borders::ALL).title("Todos"))
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(self.theme.border)
+                    .title(list_title),
+            )
             .widths(&[Constraint::Percentage(100)]);
-        f.render_widget(list, chunks[0]);
+        let list_height = chunks[0].height.saturating_sub(2);
+        self.list_height.set(list_height);
+        let offset = scroll_offset(&row_heights, selected_row, self.list_offset.get(), list_height);
+        self.list_offset.set(offset);
+        self.table_state.borrow_mut().select(selected_row);
+        f.render_stateful_widget(list, chunks[0], &mut self.table_state.borrow_mut());
+        render_scrollbar(f, chunks[0], &row_heights, offset, self.theme.header);
+        self.list_area.set(chunks[0]);
+        *self.list_rows.borrow_mut() = row_tasks.into_iter().zip(row_heights).collect();
 
         // Build task details if selected
         if self.current_idx.is_some() {
@@ -321,6 +1436,51 @@ where
             let date = Spans::from(date_text);
             details.push(date);
 
+            let status_text = format!("Status: {}", task.status);
+            details.push(Spans::from(status_text));
+
+            if let Some(project) = &task.project {
+                details.push(Spans::from(format!("Project: {}", project)));
+            }
+
+            if let Some(priority) = &task.priority {
+                details.push(Spans::from(format!("Priority: {}", priority)));
+            }
+
+            if !task.tags.is_empty() {
+                let tags_text = format!("Tags: {}", task.tags.join(", "));
+                details.push(Spans::from(tags_text));
+            }
+
+            if let Some(estimate) = &task.estimate {
+                let estimate_text = format!("Estimate: {}", utils::format_duration(estimate));
+                details.push(Spans::from(estimate_text));
+            }
+
+            if !task.reminders.is_empty() {
+                let reminders = utils::format_durations(&task.reminders);
+                let reminders_text = format!("Reminders: {} before", reminders);
+                details.push(Spans::from(reminders_text));
+            }
+
+            if !task.time_entries.is_empty() {
+                let running = if task.is_timer_running() { " (running)" } else { "" };
+                let time_text = format!(
+                    "Time tracked: {}{}",
+                    utils::format_duration(&task.total_time()),
+                    running
+                );
+                details.push(Spans::from(time_text));
+            }
+
+            if let Some(scheduled) = &task.scheduled {
+                let settings = &self.app.borrow().settings;
+                let scheduled = utils::start_date_to_display_str(scheduled, settings);
+                let scheduled_text = format!("Scheduled: {}", scheduled);
+                let scheduled = Spans::from(scheduled_text);
+                details.push(scheduled);
+            }
+
             let repeats_text = task.repeats.to_string();
             if task.repeats != Repeat::Never {
                 let repeats_text = format!("Repeats: {}", repeats_text);
@@ -328,17 +1488,103 @@ where
                 details.push(repeats);
             }
 
+            for (idx, link) in task.all_links().iter().enumerate() {
+                details.push(Spans::from(format!("Link {}: {}", idx + 1, link)));
+            }
+
             let desc_text = task.description.clone().unwrap_or_default();
-            if !desc_text.is_empty() {
-                let desc_text = format!("Description: {}", desc_text);
-                let desc = Spans::from(desc_text);
-                details.push(desc);
+            if !desc_text.trim().is_empty() {
+                let icons = &self.app.borrow().settings.icons;
+                details.push(Spans::from(""));
+                details.extend(markdown::render(&desc_text, &self.theme, icons));
             }
 
             let details = Paragraph::new(details)
-                .block(Block::default().borders(Borders::ALL).title("Description"))
-                .wrap(Wrap { trim: true });
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .border_style(self.theme.border)
+                        .title("Description"),
+                )
+                .wrap(Wrap { trim: false });
             f.render_widget(details, chunks[1]);
         }
+
+        if let Some(picker) = &self.list_picker {
+            self.render_list_picker(f, picker);
+        }
+
+        if self.show_help {
+            let extra = [("0-9", "switch views")];
+            help::render_help(f, &self.keymaps.all_tasks, &self.theme, &extra);
+        }
+    }
+}
+
+/// The first row shown so the selected one stays visible, the way `Table` scrolls
+fn scroll_offset(heights: &[u16], selected: Option<usize>, offset: usize, max_height: u16) -> usize {
+    if heights.is_empty() || selected.is_none() {
+        return 0;
+    }
+
+    let mut start = offset.min(heights.len() - 1);
+    let mut end = start;
+    let mut height = 0;
+    for &row_height in heights.iter().skip(start) {
+        if height + 1 > max_height {
+            break;
+        }
+        height += row_height;
+        end += 1;
+    }
+
+    let selected = selected.unwrap().min(heights.len() - 1);
+    while selected >= end {
+        height = height.saturating_add(heights[end]);
+        end += 1;
+        while height > max_height {
+            height = height.saturating_sub(heights[start]);
+            start += 1;
+        }
+    }
+    while selected < start {
+        start -= 1;
+        height = height.saturating_add(heights[start]);
+        while height > max_height {
+            end -= 1;
+            height = height.saturating_sub(heights[end]);
+        }
     }
+    start
+}
+
+/// Draws a scrollbar over the right border when the rows don't fit
+fn render_scrollbar<B: Backend>(
+    f: &mut Frame<B>,
+    area: Rect,
+    heights: &[u16],
+    offset: usize,
+    style: Style,
+) {
+    let track_height = area.height.saturating_sub(2);
+    let total: u16 = heights.iter().sum();
+    if track_height == 0 || total <= track_height {
+        return;
+    }
+
+    let above: u16 = heights.iter().take(offset).sum();
+    let thumb_height = (track_height as u32 * track_height as u32 / total as u32).max(1) as u16;
+    let max_thumb_start = track_height - thumb_height;
+    let scrollable = (total - track_height) as u32;
+    let thumb_start = (above as u32 * max_thumb_start as u32 / scrollable) as u16;
+    let thumb_start = thumb_start.min(max_thumb_start);
+
+    let thumb = Paragraph::new(vec![Spans::from("┃"); thumb_height as usize]).style(style);
+    let thumb_area = Rect::new(
+        area.x + area.width - 1,
+        area.y + 1 + thumb_start,
+        1,
+        thumb_height,
+    );
+    f.render_widget(thumb, thumb_area);
 }
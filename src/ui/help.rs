@@ -0,0 +1,58 @@
+use crate::keybindings::Keymap;
+use crate::theme::Theme;
+use tui::{
+    backend::Backend,
+    layout::{Constraint, Rect},
+    style::{Modifier, Style},
+    text::Span,
+    widgets::{Block, Borders, Cell, Clear, Row, Table},
+    Frame,
+};
+
+/// A popup listing every action of a page with its keys
+pub fn render_help<B: Backend>(
+    f: &mut Frame<B>,
+    keymap: &Keymap,
+    theme: &Theme,
+    extra: &[(&str, &str)],
+) {
+    let mut help: Vec<(String, String)> = keymap
+        .help()
+        .into_iter()
+        .map(|(keys, description)| (keys, description.to_string()))
+        .collect();
+    help.extend(extra.iter().map(|(k, d)| (k.to_string(), d.to_string())));
+
+    let keys_width = help.iter().map(|(k, _)| k.len()).max().unwrap_or(0) as u16;
+    let rows = help.into_iter().map(|(keys, description)| {
+        let keys = Span::styled(keys, theme.message);
+        Row::new(vec![Cell::from(keys), Cell::from(description)])
+    });
+
+    let area = centered_rect(f.size(), 60, rows.len() as u16 + 2);
+    let description_width = area.width.saturating_sub(keys_width + 4);
+    let widths = [Constraint::Length(keys_width), Constraint::Length(description_width)];
+    let table = Table::new(rows)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(theme.border)
+                .title(Span::styled("Keys", Style::default().add_modifier(Modifier::BOLD))),
+        )
+        .column_spacing(2)
+        .widths(&widths);
+    f.render_widget(Clear, area);
+    f.render_widget(table, area);
+}
+
+/// A rectangle in the middle of `area`, shrunk to fit
+pub fn centered_rect(area: Rect, width: u16, height: u16) -> Rect {
+    let width = width.min(area.width);
+    let height = height.min(area.height);
+    Rect::new(
+        area.x + (area.width - width) / 2,
+        area.y + (area.height - height) / 2,
+        width,
+        height,
+    )
+}
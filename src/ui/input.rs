@@ -0,0 +1,283 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::cell::Cell;
+use tui::{
+    backend::Backend,
+    layout::Rect,
+    style::Style,
+    widgets::{Block, Paragraph},
+    Frame,
+};
+use unicode_width::UnicodeWidthChar;
+
+/// A text input with a cursor. Single line unless created with `multiline`.
+/// Lines wider than the field scroll sideways
+#[derive(Default)]
+pub struct TextInput {
+    chars: Vec<char>,
+    /// The cursor position, in chars
+    cursor: usize,
+    multiline: bool,
+    /// The first column and line shown
+    scroll: Cell<usize>,
+    scroll_row: Cell<usize>,
+}
+
+impl TextInput {
+    pub fn new(value: &str) -> TextInput {
+        let chars: Vec<char> = value.chars().collect();
+        TextInput {
+            cursor: chars.len(),
+            chars,
+            ..Default::default()
+        }
+    }
+
+    /// An input where Enter starts a new line
+    pub fn multiline(value: &str) -> TextInput {
+        TextInput {
+            multiline: true,
+            ..TextInput::new(value)
+        }
+    }
+
+    pub fn value(&self) -> String {
+        self.chars.iter().collect()
+    }
+
+    /// Edits the value or moves the cursor. Returns whether the key was used
+    pub fn handle_key(&mut self, key: KeyEvent) -> bool {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        let alt = key.modifiers.contains(KeyModifiers::ALT);
+        match key.code {
+            KeyCode::Char('a') if ctrl => self.cursor = self.line_start(),
+            KeyCode::Char('e') if ctrl => self.cursor = self.line_end(),
+            KeyCode::Char('b') if ctrl => self.move_left(),
+            KeyCode::Char('f') if ctrl => self.move_right(),
+            KeyCode::Char('b') if alt => self.cursor = self.word_start(),
+            KeyCode::Char('f') if alt => self.cursor = self.word_end(),
+            KeyCode::Char('w') if ctrl => self.delete_word(),
+            KeyCode::Backspace if alt => self.delete_word(),
+            KeyCode::Char('u') if ctrl => {
+                let start = self.line_start();
+                self.chars.drain(start..self.cursor);
+                self.cursor = start;
+            }
+            KeyCode::Char('k') if ctrl => {
+                let end = self.line_end();
+                self.chars.drain(self.cursor..end);
+            }
+            KeyCode::Char('d') if ctrl => self.delete(),
+            KeyCode::Char(_) if ctrl || alt => return false,
+            KeyCode::Char(c) => self.insert(c),
+            KeyCode::Enter if self.multiline => self.insert('\n'),
+            KeyCode::Backspace => self.backspace(),
+            KeyCode::Delete => self.delete(),
+            KeyCode::Left if ctrl => self.cursor = self.word_start(),
+            KeyCode::Right if ctrl => self.cursor = self.word_end(),
+            KeyCode::Left => self.move_left(),
+            KeyCode::Right => self.move_right(),
+            KeyCode::Up if self.multiline => self.move_line(-1),
+            KeyCode::Down if self.multiline => self.move_line(1),
+            KeyCode::Home => self.cursor = self.line_start(),
+            KeyCode::End => self.cursor = self.line_end(),
+            _ => return false,
+        }
+        true
+    }
+
+    pub fn insert(&mut self, c: char) {
+        self.chars.insert(self.cursor, c);
+        self.cursor += 1;
+    }
+
+    /// Inserts pasted text. Single line inputs join its lines with spaces
+    pub fn insert_str(&mut self, s: &str) {
+        let mut text = s.replace("\r\n", "\n").replace('\r', "\n");
+        if !self.multiline {
+            text = text.trim_end_matches('\n').replace('\n', " ");
+        }
+        for c in text.chars() {
+            self.insert(c);
+        }
+    }
+
+    pub fn backspace(&mut self) {
+        if self.cursor > 0 {
+            self.cursor -= 1;
+            self.chars.remove(self.cursor);
+        }
+    }
+
+    pub fn delete(&mut self) {
+        if self.cursor < self.chars.len() {
+            self.chars.remove(self.cursor);
+        }
+    }
+
+    fn move_left(&mut self) {
+        self.cursor = self.cursor.saturating_sub(1);
+    }
+
+    fn move_right(&mut self) {
+        self.cursor = (self.cursor + 1).min(self.chars.len());
+    }
+
+    /// Moves to the same column of the line above or below, or as close as it gets
+    fn move_line(&mut self, delta: i32) {
+        let column = self.cursor - self.line_start();
+        self.cursor = if delta < 0 {
+            match self.line_start() {
+                0 => return,
+                start => start - 1,
+            }
+        } else {
+            match self.line_end() {
+                end if end == self.chars.len() => return,
+                end => end + 1,
+            }
+        };
+        self.cursor = (self.line_start() + column).min(self.line_end());
+    }
+
+    /// Deletes the word before the cursor, like readline's Ctrl-w
+    fn delete_word(&mut self) {
+        let start = self.word_start();
+        self.chars.drain(start..self.cursor);
+        self.cursor = start;
+    }
+
+    fn word_start(&self) -> usize {
+        let mut idx = self.cursor;
+        while idx > 0 && self.chars[idx - 1].is_whitespace() {
+            idx -= 1;
+        }
+        while idx > 0 && !self.chars[idx - 1].is_whitespace() {
+            idx -= 1;
+        }
+        idx
+    }
+
+    fn word_end(&self) -> usize {
+        let mut idx = self.cursor;
+        while idx < self.chars.len() && self.chars[idx].is_whitespace() {
+            idx += 1;
+        }
+        while idx < self.chars.len() && !self.chars[idx].is_whitespace() {
+            idx += 1;
+        }
+        idx
+    }
+
+    /// Where the line with the cursor starts
+    fn line_start(&self) -> usize {
+        self.chars[..self.cursor]
+            .iter()
+            .rposition(|&c| c == '\n')
+            .map_or(0, |idx| idx + 1)
+    }
+
+    /// Where the line with the cursor ends, before its newline
+    fn line_end(&self) -> usize {
+        self.chars[self.cursor..]
+            .iter()
+            .position(|&c| c == '\n')
+            .map_or(self.chars.len(), |idx| self.cursor + idx)
+    }
+
+    /// The line the cursor is on
+    fn cursor_row(&self) -> usize {
+        self.chars[..self.cursor].iter().filter(|&&c| c == '\n').count()
+    }
+
+    fn lines(&self) -> Vec<&[char]> {
+        self.chars.split(|&c| c == '\n').collect()
+    }
+
+    /// Moves the cursor to a position of the drawn field, e.g. after a click
+    pub fn click(&mut self, area: Rect, column: u16, row: u16) {
+        let lines = self.lines();
+        let line_idx = self.scroll_row.get() + row.saturating_sub(area.y + 1) as usize;
+        let line_idx = line_idx.min(lines.len() - 1);
+        let line_start: usize = lines[..line_idx].iter().map(|line| line.len() + 1).sum();
+        let line = lines[line_idx];
+
+        let mut x = area.x + 1;
+        let mut idx = self.scroll.get().min(line.len());
+        while idx < line.len() {
+            let width = char_width(line[idx]);
+            if column < x + width {
+                break;
+            }
+            x += width;
+            idx += 1;
+        }
+        self.cursor = line_start + idx;
+    }
+
+    /// Draws the visible part of the value inside the block
+    pub fn render<B: Backend>(&self, f: &mut Frame<B>, area: Rect, block: Block, style: Style) {
+        let width = area.width.saturating_sub(2);
+        let height = area.height.saturating_sub(2).max(1) as usize;
+        self.scroll_to_cursor(width, height);
+
+        let text: Vec<String> = self
+            .lines()
+            .into_iter()
+            .skip(self.scroll_row.get())
+            .take(height)
+            .map(|line| {
+                let mut text = String::new();
+                let mut used = 0;
+                for &c in line.iter().skip(self.scroll.get()) {
+                    used += char_width(c);
+                    if used > width {
+                        break;
+                    }
+                    text.push(c);
+                }
+                text
+            })
+            .collect();
+        f.render_widget(Paragraph::new(text.join("\n")).style(style).block(block), area);
+    }
+
+    /// Puts the terminal cursor where the next char goes
+    pub fn set_cursor<B: Backend>(&self, f: &mut Frame<B>, area: Rect) {
+        let start = (self.line_start() + self.scroll.get()).min(self.cursor);
+        let before: u16 = self.chars[start..self.cursor]
+            .iter()
+            .map(|&c| char_width(c))
+            .sum();
+        let row = self.cursor_row().saturating_sub(self.scroll_row.get()) as u16;
+        f.set_cursor(area.x + 1 + before, area.y + 1 + row);
+    }
+
+    /// Scrolls just enough to keep the cursor inside `width` columns and `height`
+    /// lines, and back when the end of the line leaves room
+    fn scroll_to_cursor(&self, width: u16, height: usize) {
+        let row = self.cursor_row();
+        let mut scroll_row = self.scroll_row.get().min(row);
+        if row >= scroll_row + height {
+            scroll_row = row + 1 - height;
+        }
+        self.scroll_row.set(scroll_row);
+
+        let line = &self.chars[self.line_start()..self.line_end()];
+        let column = self.cursor - self.line_start();
+        let text_width = |start: usize, end: usize| -> u16 {
+            line[start..end].iter().map(|&c| char_width(c)).sum()
+        };
+        let mut scroll = self.scroll.get().min(column);
+        while scroll < column && text_width(scroll, column) >= width {
+            scroll += 1;
+        }
+        while scroll > 0 && text_width(scroll - 1, line.len()) < width {
+            scroll -= 1;
+        }
+        self.scroll.set(scroll);
+    }
+}
+
+fn char_width(c: char) -> u16 {
+    c.width().unwrap_or(0) as u16
+}
@@ -0,0 +1,219 @@
+use crate::configuration::Icons;
+use crate::status::Status;
+use crate::theme::Theme;
+use tui::{
+    style::{Modifier, Style},
+    text::{Span, Spans},
+};
+
+/// Renders the Markdown most descriptions use: headings, lists, checklists,
+/// quotes, code and bold or italic text
+pub fn render(text: &str, theme: &Theme, icons: &Icons) -> Vec<Spans<'static>> {
+    let mut lines = vec![];
+    let mut in_code_block = false;
+    for line in text.lines() {
+        let trimmed = line.trim_start();
+        let indent = line[..line.len() - trimmed.len()].to_string();
+
+        if trimmed.starts_with("```") {
+            in_code_block = !in_code_block;
+            continue;
+        }
+        if in_code_block {
+            lines.push(Spans::from(Span::styled(line.to_string(), theme.code)));
+            continue;
+        }
+
+        let spans = if let Some((level, heading)) = parse_heading(trimmed) {
+            let style = match level {
+                1 => theme.header.add_modifier(Modifier::UNDERLINED),
+                _ => theme.header,
+            };
+            inline_spans(heading, style, theme)
+        } else if let Some((checked, item)) = parse_checkbox(trimmed) {
+            let (icon, style) = match checked {
+                true => (icons.get_status_icon(Status::Complete), theme.complete),
+                false => (icons.get_status_icon(Status::Todo), Style::default()),
+            };
+            let mut spans = vec![Span::raw(indent), Span::styled(format!("{} ", icon.trim()), style)];
+            spans.extend(inline_spans(item, style, theme));
+            spans
+        } else if let Some(item) = parse_bullet(trimmed) {
+            let mut spans = vec![Span::raw(format!("{}• ", indent))];
+            spans.extend(inline_spans(item, Style::default(), theme));
+            spans
+        } else if let Some(quote) = trimmed.strip_prefix('>') {
+            let style = theme.muted.add_modifier(Modifier::ITALIC);
+            let mut spans = vec![Span::styled(format!("{}│ ", indent), theme.muted)];
+            spans.extend(inline_spans(quote.trim_start(), style, theme));
+            spans
+        } else if is_rule(trimmed) {
+            vec![Span::styled("─".repeat(20), theme.muted)]
+        } else {
+            let mut spans = vec![Span::raw(indent)];
+            spans.extend(inline_spans(trimmed, Style::default(), theme));
+            spans
+        };
+        lines.push(Spans::from(spans));
+    }
+    lines
+}
+
+fn parse_heading(line: &str) -> Option<(usize, &str)> {
+    let level = line.chars().take_while(|&c| c == '#').count();
+    if !(1..=6).contains(&level) {
+        return None;
+    }
+    line[level..].strip_prefix(' ').map(|heading| (level, heading.trim()))
+}
+
+fn parse_bullet(line: &str) -> Option<&str> {
+    ["- ", "* ", "+ "].iter().find_map(|bullet| line.strip_prefix(bullet))
+}
+
+/// `- [ ] item` or `- [x] item`, with whether it's checked
+fn parse_checkbox(line: &str) -> Option<(bool, &str)> {
+    let item = parse_bullet(line)?;
+    let checked = match item.get(..3)? {
+        "[ ]" => false,
+        "[x]" | "[X]" => true,
+        _ => return None,
+    };
+    Some((checked, item[3..].trim_start()))
+}
+
+fn is_rule(line: &str) -> bool {
+    let line = line.replace(' ', "");
+    line.len() >= 3 && ["-", "*", "_"].iter().any(|c| line.chars().all(|l| l.to_string() == *c))
+}
+
+/// Splits a line into spans for `code`, **bold** and *italic* text
+fn inline_spans(line: &str, base: Style, theme: &Theme) -> Vec<Span<'static>> {
+    let chars: Vec<char> = line.chars().collect();
+    let closes = |from: usize, marker: &[char]| {
+        (from..chars.len()).any(|idx| chars[idx..].starts_with(marker))
+    };
+
+    let mut spans = vec![];
+    let mut text = String::new();
+    let (mut bold, mut italic) = (false, false);
+    let style = |bold: bool, italic: bool| {
+        let mut style = base;
+        if bold {
+            style = style.add_modifier(Modifier::BOLD);
+        }
+        if italic {
+            style = style.add_modifier(Modifier::ITALIC);
+        }
+        style
+    };
+
+    let mut idx = 0;
+    while idx < chars.len() {
+        let c = chars[idx];
+        let prev_is_word = idx > 0 && chars[idx - 1].is_alphanumeric();
+        let next = chars.get(idx + 1).copied();
+
+        if c == '`' {
+            if let Some(len) = chars[idx + 1..].iter().position(|&c| c == '`') {
+                spans.push(Span::styled(std::mem::take(&mut text), style(bold, italic)));
+                let code: String = chars[idx + 1..idx + 1 + len].iter().collect();
+                spans.push(Span::styled(code, base.patch(theme.code)));
+                idx += len + 2;
+                continue;
+            }
+        }
+
+        // Underscores inside words, like snake_case, stay as they are
+        let is_marker = c == '*' || (c == '_' && !prev_is_word);
+        if is_marker && next == Some(c) && (bold || closes(idx + 2, &[c, c])) {
+            spans.push(Span::styled(std::mem::take(&mut text), style(bold, italic)));
+            bold = !bold;
+            idx += 2;
+            continue;
+        }
+        if is_marker && (italic || closes(idx + 1, &[c])) {
+            spans.push(Span::styled(std::mem::take(&mut text), style(bold, italic)));
+            italic = !italic;
+            idx += 1;
+            continue;
+        }
+
+        text.push(c);
+        idx += 1;
+    }
+    spans.push(Span::styled(text, style(bold, italic)));
+    spans.retain(|span| !span.content.is_empty());
+    spans
+}
+
+/// The lines outside fenced code blocks, with their index in the text
+fn lines_outside_code(text: &str) -> impl Iterator<Item = (usize, &str)> {
+    let mut in_code_block = false;
+    text.lines().enumerate().filter(move |(_, line)| {
+        if line.trim_start().starts_with("```") {
+            in_code_block = !in_code_block;
+            return false;
+        }
+        !in_code_block
+    })
+}
+
+/// The line of each checklist item, whether it's checked and its text
+pub fn checklist(text: &str) -> Vec<(usize, bool, String)> {
+    lines_outside_code(text)
+        .filter_map(|(idx, line)| {
+            parse_checkbox(line.trim_start()).map(|(checked, item)| (idx, checked, item.to_string()))
+        })
+        .collect()
+}
+
+/// Checks or unchecks the checklist item on a line of the text, keeping the line endings
+pub fn toggle_checkbox(text: &str, line_idx: usize) -> String {
+    if !checklist(text).iter().any(|(idx, _, _)| *idx == line_idx) {
+        return text.to_string();
+    }
+
+    text.split_inclusive('\n')
+        .enumerate()
+        .map(|(idx, line)| {
+            let content = line.trim_end_matches(&['\r', '\n'][..]);
+            let trimmed = content.trim_start();
+            match parse_checkbox(trimmed) {
+                Some((checked, item)) if idx == line_idx => {
+                    let indent = &content[..content.len() - trimmed.len()];
+                    let bullet = &trimmed[..2];
+                    let mark = if checked { ' ' } else { 'x' };
+                    let ending = &line[content.len()..];
+                    format!("{}{}[{}] {}{}", indent, bullet, mark, item, ending)
+                }
+                _ => line.to_string(),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEXT: &str = "Steps\n- [ ] one\n```\n- [ ] not an item\n```\n  * [x] two\n";
+
+    #[test]
+    fn skips_items_in_code_blocks() {
+        let items = checklist(TEXT);
+        assert_eq!(items, [(1, false, "one".to_string()), (5, true, "two".to_string())]);
+        assert_eq!(toggle_checkbox(TEXT, 3), TEXT);
+    }
+
+    #[test]
+    fn toggles_items_and_keeps_line_endings() {
+        let toggled = toggle_checkbox(TEXT, 1);
+        assert_eq!(toggled, TEXT.replace("- [ ] one", "- [x] one"));
+        let toggled = toggle_checkbox(&toggled, 5);
+        assert_eq!(toggled, TEXT.replace("- [ ] one", "- [x] one").replace("[x] two", "[ ] two"));
+
+        let windows = "- [ ] one\r\n- [ ] two\r\n";
+        assert_eq!(toggle_checkbox(windows, 1), "- [ ] one\r\n- [x] two\r\n");
+    }
+}
@@ -1,7 +1,9 @@
 use crate::app::App;
+use crate::keybindings::Keymaps;
+use crate::theme::Theme;
 use anyhow::Result;
 use crossterm::{
-    event::{DisableMouseCapture, EnableMouseCapture},
+    event::{DisableBracketedPaste, DisableMouseCapture, EnableBracketedPaste, EnableMouseCapture},
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
@@ -14,33 +16,68 @@ use tui::{
 };
 
 mod all_tasks;
+mod help;
+mod input;
+mod markdown;
+mod picker;
 mod new_task;
 
 use new_task::NewTaskPage;
 use all_tasks::AllTasksPage;
 
 pub fn start_ui(app: App) -> Result<()> {
-    let mut stdout = stdout();
-    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
-    enable_raw_mode()?;
-    let backend = CrosstermBackend::new(stdout);
+    // Check the keybindings and theme before taking over the terminal
+    let keymaps = Rc::new(Keymaps::new(&app.settings.keybindings)?);
+    let theme = Rc::new(Theme::new(&app.settings.theme)?);
+
+    let mouse = app.settings.mouse;
+    take_terminal(mouse)?;
+    let backend = CrosstermBackend::new(stdout());
     let mut terminal = Terminal::new(backend)?;
     terminal.hide_cursor()?;
 
-    run_app(&mut terminal, app)?;
+    run_app(&mut terminal, app, keymaps, theme)?;
 
     // restore terminal
-    disable_raw_mode()?;
-    execute!(
-        terminal.backend_mut(),
-        LeaveAlternateScreen,
-        DisableMouseCapture
-    )?;
+    restore_terminal(mouse)?;
     terminal.show_cursor()?;
 
     Ok(())
 }
 
+fn take_terminal(mouse: bool) -> Result<()> {
+    execute!(stdout(), EnterAlternateScreen, EnableBracketedPaste)?;
+    if mouse {
+        execute!(stdout(), EnableMouseCapture)?;
+    }
+    enable_raw_mode()?;
+    Ok(())
+}
+
+fn restore_terminal(mouse: bool) -> Result<()> {
+    disable_raw_mode()?;
+    execute!(stdout(), LeaveAlternateScreen, DisableBracketedPaste)?;
+    if mouse {
+        execute!(stdout(), DisableMouseCapture)?;
+    }
+    Ok(())
+}
+
+/// Gives the terminal back while `f` runs, e.g. to open an editor
+pub fn suspend<B: Backend, T>(
+    terminal: &mut Terminal<B>,
+    mouse: bool,
+    f: impl FnOnce() -> T,
+) -> Result<T> {
+    restore_terminal(mouse)?;
+    terminal.show_cursor()?;
+    let result = f();
+    take_terminal(mouse)?;
+    terminal.hide_cursor()?;
+    terminal.clear()?;
+    Ok(result)
+}
+
 #[derive(Eq, PartialEq)]
 pub enum UIPage {
     Quit,
@@ -55,28 +92,158 @@ pub trait Page<B: Backend> {
     fn ui(&self, f: &mut Frame<B>);
 }
 
-fn run_app<B: Backend>(terminal: &mut Terminal<B>, app: App) -> Result<()> {
-    let app = Rc::new(RefCell::new(app));
-    let mut curr_page: Box<dyn Page<B>> = Box::new(AllTasksPage::new(Rc::clone(&app)));
+/// The task list, and the form when one is open. The list is kept while the form is
+/// open, so its undo history, filter and message are still there when it closes
+struct Pages {
+    all_tasks: AllTasksPage,
+    form: Option<NewTaskPage>,
+    /// The task selected in the list when the form opened
+    selected: Option<usize>,
+    app: Rc<RefCell<App>>,
+    keymaps: Rc<Keymaps>,
+    theme: Rc<Theme>,
+}
 
-    loop {
-        let new_page_type = curr_page.render(terminal)?;
+impl Pages {
+    fn new(app: Rc<RefCell<App>>, keymaps: Rc<Keymaps>, theme: Rc<Theme>) -> Pages {
+        let all_tasks = AllTasksPage::new(Rc::clone(&app), Rc::clone(&keymaps), Rc::clone(&theme));
+        Pages {
+            all_tasks,
+            form: None,
+            selected: None,
+            app,
+            keymaps,
+            theme,
+        }
+    }
+
+    fn current<B: Backend>(&mut self) -> &mut dyn Page<B> {
+        match &mut self.form {
+            Some(form) => form,
+            None => &mut self.all_tasks,
+        }
+    }
 
-        match new_page_type {
-            UIPage::Quit => break,
+    /// Goes to another page. Returns false to quit
+    fn switch(&mut self, page: UIPage) -> bool {
+        match page {
+            UIPage::Quit => return false,
+            UIPage::SamePage => {}
             UIPage::AllTasks => {
-                curr_page = Box::new(AllTasksPage::new(Rc::clone(&app)));
+                self.form = None;
+                // Saving the form sorts the tasks again, so find the selected one by ID
+                match self.selected.take() {
+                    Some(task_id) => self.all_tasks.select_task(task_id),
+                    None => self.all_tasks.move_closest(),
+                }
             }
             UIPage::NewTask => {
-                curr_page = Box::new(NewTaskPage::new(Rc::clone(&app)));
+                self.selected = self.all_tasks.get_current_task_id();
+                self.form = Some(NewTaskPage::new(
+                    Rc::clone(&self.app),
+                    Rc::clone(&self.keymaps),
+                    Rc::clone(&self.theme),
+                ));
             }
             UIPage::EditTask(task_id) => {
-                curr_page = Box::new(NewTaskPage::new_from_task(Rc::clone(&app), task_id));
+                self.selected = self.all_tasks.get_current_task_id();
+                self.form = Some(NewTaskPage::new_from_task(
+                    Rc::clone(&self.app),
+                    Rc::clone(&self.keymaps),
+                    Rc::clone(&self.theme),
+                    task_id,
+                ));
             }
-            _ => {}
+        }
+        true
+    }
+}
+
+fn run_app<B: Backend>(
+    terminal: &mut Terminal<B>,
+    app: App,
+    keymaps: Rc<Keymaps>,
+    theme: Rc<Theme>,
+) -> Result<()> {
+    let app = Rc::new(RefCell::new(app));
+    let mut pages = Pages::new(app, keymaps, theme);
+
+    loop {
+        let new_page_type = pages.current().render(terminal)?;
+        if !pages.switch(new_page_type) {
+            break;
         }
     }
-    
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::configuration::Settings;
+    use crate::task::Task;
+
+    fn pages(db_file: &std::path::Path) -> Pages {
+        std::fs::write(db_file, "[]").unwrap();
+        let settings = Settings::with_db_file(db_file.to_string_lossy().to_string());
+        let keymaps = Rc::new(Keymaps::new(&settings.keybindings).unwrap());
+        let theme = Rc::new(Theme::new(&settings.theme).unwrap());
+        let mut app = App::new(settings);
+        for name in ["first", "second"] {
+            let mut task = Task::new();
+            task.set_name(name.to_string());
+            app.add_task(task);
+        }
+        Pages::new(Rc::new(RefCell::new(app)), keymaps, theme)
+    }
+
+    #[test]
+    fn keeps_undo_and_filter_after_the_form() {
+        let name = format!("todo-rs-pages-{}.json", std::process::id());
+        let db_file = std::env::temp_dir().join(name);
+        let mut pages = pages(&db_file);
+
+        pages.all_tasks.select_task(2);
+        pages.all_tasks.tag_selected("work");
+        pages.all_tasks.set_filter("tag:work").unwrap();
+
+        assert!(pages.switch(UIPage::EditTask(2)));
+        assert!(pages.form.is_some());
+        assert!(pages.switch(UIPage::AllTasks));
+        assert!(pages.form.is_none());
+
+        assert_eq!(pages.all_tasks.get_current_task_id(), Some(2));
+        assert!(pages.all_tasks.filter.is_some());
+        let (message, _) = pages.all_tasks.message.clone().unwrap();
+        assert!(message.starts_with("Task retagged"));
+        pages.all_tasks.undo();
+        assert!(pages.app.borrow().get_task(2).unwrap().tags.is_empty());
+
+        assert!(!pages.switch(UIPage::Quit));
+        std::fs::remove_file(db_file).unwrap();
+    }
+
+    #[test]
+    fn undoes_snoozes() {
+        let name = format!("todo-rs-snooze-{}.json", std::process::id());
+        let db_file = std::env::temp_dir().join(name);
+        let mut pages = pages(&db_file);
+        let page = &mut pages.all_tasks;
+        let due = pages.app.borrow().get_task(1).unwrap().date;
+
+        page.select_task(1);
+        for _ in 0..30 {
+            page.snooze_selected("1d").unwrap();
+        }
+        assert_eq!(page.undo_stack.len(), all_tasks::UNDO_LIMIT);
+
+        assert!(page.snooze_selected("someday").is_err());
+        assert_eq!(page.undo_stack.len(), all_tasks::UNDO_LIMIT);
+
+        page.undo();
+        let snoozed = pages.app.borrow().get_task(1).unwrap().date;
+        assert_eq!(snoozed.date_naive() - due.date_naive(), chrono::Duration::days(29));
+        std::fs::remove_file(db_file).unwrap();
+    }
+}
@@ -1,17 +1,35 @@
-use crate::{app::App, task_form::TaskForm, utils};
+use crate::keybindings::{Action, Keymaps};
+use crate::theme::Theme;
+use crate::task_form::{Field, TaskForm};
+use crate::{app::App, editor};
 use anyhow::Result;
-use crossterm::event::{self, Event, KeyCode};
+use crossterm::event::{self, Event, KeyCode, MouseButton, MouseEvent, MouseEventKind};
 use std::{cell::RefCell, rc::Rc};
+use unicode_width::UnicodeWidthStr;
 use tui::{
     backend::Backend,
-    layout::{Alignment, Constraint, Direction, Layout},
-    style::{Color, Style},
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::Style,
     widgets::{Block, Borders, Paragraph, Wrap},
     Frame, Terminal,
 };
-use unicode_width::UnicodeWidthStr;
 
-use super::{Page, UIPage};
+use super::input::TextInput;
+use super::picker::{DatePicker, PickerResult, RepeatPicker};
+use super::{help, Page, UIPage};
+
+/// The index of the description in the form fields
+const DESCRIPTION_FIELD: usize = 4;
+
+/// The fields with a picker: the dates and the repeats
+const DATE_FIELDS: [usize; 2] = [1, 2];
+const REPEATS_FIELD: usize = 3;
+
+/// A popup helping to fill a field
+pub enum Picker {
+    Date(DatePicker),
+    Repeat(RepeatPicker),
+}
 
 #[derive(PartialEq)]
 pub enum NewTaskInputMode {
@@ -20,44 +38,66 @@ pub enum NewTaskInputMode {
 }
 
 pub struct NewTaskPage {
-    pub task_form: TaskForm,
+    /// The form fields in the order they're drawn
+    pub inputs: Vec<TextInput>,
     pub input_mode: NewTaskInputMode,
     pub editing_task: Option<usize>,
     pub current_idx: usize,
     pub num_fields: usize,
     pub error: Option<String>,
+    pub keymaps: Rc<Keymaps>,
+    pub theme: Rc<Theme>,
+    pub show_help: bool,
+    pub picker: Option<Picker>,
+    /// Where each field was drawn, for clicks
+    pub field_areas: RefCell<Vec<Rect>>,
     pub app: Rc<RefCell<App>>,
 }
 
 impl NewTaskPage {
-    pub fn new(app: Rc<RefCell<App>>) -> NewTaskPage {
+    pub fn new(app: Rc<RefCell<App>>, keymaps: Rc<Keymaps>, theme: Rc<Theme>) -> NewTaskPage {
+        let mut task_form = TaskForm::new();
+        if let Some(view) = app.borrow().settings.get_context() {
+            view.apply_context(&mut task_form);
+        }
+
         NewTaskPage {
-            task_form: TaskForm::new(),
+            inputs: form_inputs(task_form),
             input_mode: NewTaskInputMode::Normal,
             current_idx: 0,
             error: None,
-            num_fields: 4,
+            num_fields: 11,
             editing_task: None,
+            keymaps,
+            theme,
+            show_help: false,
+            picker: None,
+            field_areas: RefCell::new(vec![]),
             app,
         }
     }
 
-    pub fn new_from_task(app: Rc<RefCell<App>>, task_id: usize) -> NewTaskPage {
+    pub fn new_from_task(
+        app: Rc<RefCell<App>>,
+        keymaps: Rc<Keymaps>,
+        theme: Rc<Theme>,
+        task_id: usize,
+    ) -> NewTaskPage {
         let task = app.borrow().get_task(task_id).unwrap().clone();
-        let mut task_form = TaskForm::new();
-
-        task_form.name = task.name.to_string();
-        task_form.date = utils::date_to_input_str(&task.date, &app.borrow().settings);
-        task_form.repeats = task.repeats.to_string();
-        task_form.description = task.description.unwrap_or("".to_string());
+        let task_form = TaskForm::from_task(&task, &app.borrow().settings);
 
         NewTaskPage {
-            task_form,
+            inputs: form_inputs(task_form),
             input_mode: NewTaskInputMode::Normal,
             current_idx: 0,
             error: None,
-            num_fields: 4,
+            num_fields: 11,
             editing_task: Some(task_id),
+            keymaps,
+            theme,
+            show_help: false,
+            picker: None,
+            field_areas: RefCell::new(vec![]),
             app,
         }
     }
@@ -74,50 +114,137 @@ impl NewTaskPage {
         }
     }
 
-    pub fn add_char(&mut self, c: char) {
-        match self.current_idx {
-            0 => {
-                self.task_form.name.push(c);
-            }
-            1 => {
-                self.task_form.date.push(c);
-            }
-            2 => {
-                self.task_form.repeats.push(c);
-            }
-            3 => {
-                self.task_form.description.push(c);
+    fn current_field_mut(&mut self) -> &mut TextInput {
+        &mut self.inputs[self.current_idx]
+    }
+
+    /// The form with the values typed so far
+    pub fn task_form(&self) -> TaskForm {
+        let mut task_form = TaskForm::new();
+        for (field, input) in form_fields(&mut task_form).into_iter().zip(&self.inputs) {
+            *field = input.value();
+        }
+        task_form
+    }
+
+    /// Opens the description in $EDITOR
+    fn edit_description<B: Backend>(&mut self, terminal: &mut Terminal<B>) -> Result<()> {
+        let description = self.inputs[DESCRIPTION_FIELD].value();
+        let mouse = self.app.borrow().settings.mouse;
+        match super::suspend(terminal, mouse, || editor::edit_text(&description, "md"))? {
+            Ok(description) => {
+                self.inputs[DESCRIPTION_FIELD] = TextInput::multiline(description.trim_end());
+                self.current_idx = DESCRIPTION_FIELD;
             }
-            _ => {}
+            Err(e) => self.error = Some(e.to_string()),
+        }
+        Ok(())
+    }
+
+    /// Opens the whole form in $EDITOR as TOML
+    fn edit_in_editor<B: Backend>(&mut self, terminal: &mut Terminal<B>) -> Result<()> {
+        let task_form = self.task_form();
+        let mouse = self.app.borrow().settings.mouse;
+        let edited = super::suspend(terminal, mouse, || {
+            editor::edit_form(&task_form, |task_form| Ok(task_form.clone()))
+        })?;
+        match edited {
+            Ok(Some(task_form)) => self.inputs = form_inputs(task_form),
+            Ok(None) => {}
+            Err(e) => self.error = Some(e.to_string()),
+        }
+        Ok(())
+    }
+
+    /// Opens the calendar on date fields and the repeat list on the repeats field
+    fn open_picker(&mut self) {
+        let value = self.inputs[self.current_idx].value();
+        self.picker = if DATE_FIELDS.contains(&self.current_idx) {
+            Some(Picker::Date(DatePicker::new(&value, &self.app.borrow().settings)))
+        } else if self.current_idx == REPEATS_FIELD {
+            Some(Picker::Repeat(RepeatPicker::new(&value)))
+        } else {
+            None
         };
     }
 
-    pub fn remove_char(&mut self) {
-        match self.current_idx {
-            0 => {
-                self.task_form.name.pop();
-            }
-            1 => {
-                self.task_form.date.pop();
-            }
-            2 => {
-                self.task_form.repeats.pop();
-            }
-            3 => {
-                self.task_form.description.pop();
-            }
-            _ => {}
+    /// Passes a key to the picker, writing its choice into the field when accepted
+    fn handle_picker_key(&mut self, key: KeyCode) {
+        let picker = self.picker.as_mut().unwrap();
+        let result = match picker {
+            Picker::Date(picker) => picker.handle_key(key),
+            Picker::Repeat(picker) => picker.handle_key(key),
         };
+        if result == PickerResult::Pending {
+            return;
+        }
+
+        let picker = self.picker.take().unwrap();
+        if result == PickerResult::Accepted {
+            let value = match picker {
+                Picker::Date(picker) => picker.value(&self.app.borrow().settings),
+                Picker::Repeat(picker) => picker.value(),
+            };
+            self.inputs[self.current_idx] = TextInput::new(&value);
+        }
+    }
+
+    /// Clicking a field starts editing it
+    fn handle_mouse(&mut self, mouse: MouseEvent) {
+        if mouse.kind != MouseEventKind::Down(MouseButton::Left) {
+            return;
+        }
+        if self.show_help || self.picker.is_some() {
+            self.show_help = false;
+            self.picker = None;
+            return;
+        }
+
+        let clicked = self.field_areas.borrow().iter().position(|area| {
+            (area.x..area.x + area.width).contains(&mouse.column)
+                && (area.y..area.y + area.height).contains(&mouse.row)
+        });
+        if let Some(idx) = clicked {
+            self.current_idx = idx;
+            self.input_mode = NewTaskInputMode::Editing;
+            let area = self.field_areas.borrow()[idx];
+            self.current_field_mut().click(area, mouse.column, mouse.row);
+        }
     }
 
     fn border_style(&self, idx: usize) -> Style {
         if self.current_idx == idx && self.input_mode == NewTaskInputMode::Editing {
-            Style::default().fg(Color::Yellow)
+            self.theme.active
         } else {
             Style::default()
         }
     }
 
+    /// Shows what a field reads as, or why it's invalid, on its bottom border
+    fn render_preview<B: Backend>(
+        &self,
+        f: &mut Frame<B>,
+        area: Rect,
+        preview: Result<Option<String>>,
+    ) {
+        let (text, style) = match preview {
+            Ok(Some(preview)) => (format!(" → {} ", preview), self.theme.muted),
+            Ok(None) => return,
+            Err(e) => (format!(" {} ", e), self.theme.error),
+        };
+        let width = (text.width() as u16).min(area.width.saturating_sub(2));
+        if width == 0 || area.height < 2 {
+            return;
+        }
+        let preview_area = Rect::new(
+            area.x + area.width - 1 - width,
+            area.y + area.height - 1,
+            width,
+            1,
+        );
+        f.render_widget(Paragraph::new(text).style(style), preview_area);
+    }
+
     fn get_date_hint(&self) -> String {
         let date_hint = self
             .app
@@ -144,30 +271,65 @@ where
     fn render(&mut self, terminal: &mut Terminal<B>) -> Result<UIPage> {
         terminal.draw(|f| self.ui(f))?;
 
-        if let Event::Key(key) = event::read()? {
+        let event = event::read()?;
+        if let Event::Mouse(mouse) = event {
+            self.handle_mouse(mouse);
+            return Ok(UIPage::SamePage);
+        }
+        if let Event::Paste(text) = event {
+            self.input_mode = NewTaskInputMode::Editing;
+            self.current_field_mut().insert_str(&text);
+            return Ok(UIPage::SamePage);
+        }
+
+        if let Event::Key(key) = event {
+            // Any key closes the help
+            if self.show_help {
+                self.show_help = false;
+                return Ok(UIPage::SamePage);
+            }
+
+            if self.picker.is_some() {
+                self.handle_picker_key(key.code);
+                return Ok(UIPage::SamePage);
+            }
+
+            let action = self.keymaps.new_task.action_for(&key, false);
             match self.input_mode {
-                NewTaskInputMode::Normal => match key.code {
-                    KeyCode::Char('j') => self.next_field(),
-                    KeyCode::Char('k') => self.prev_field(),
-                    KeyCode::Char('q') => {
+                NewTaskInputMode::Normal => match action {
+                    Some(Action::Down) => self.next_field(),
+                    Some(Action::Up) => self.prev_field(),
+                    Some(Action::Quit) => {
                         return Ok(UIPage::Quit);
                     }
-                    KeyCode::Char('i') => {
+                    Some(Action::Insert) => {
                         self.input_mode = NewTaskInputMode::Editing;
                     }
-                    KeyCode::Char('b') => {
+                    Some(Action::Back) => {
                         return Ok(UIPage::AllTasks);
                     }
-                    KeyCode::Enter => {
+                    Some(Action::Help) => self.show_help = true,
+                    Some(Action::Pick) => self.open_picker(),
+                    Some(Action::EditDescription) => self.edit_description(terminal)?,
+                    Some(Action::EditInEditor) => self.edit_in_editor(terminal)?,
+                    Some(Action::Save) => {
                         let mut app = self.app.borrow_mut();
+                        let editing_task = self
+                            .editing_task
+                            .and_then(|task_id| app.get_task(task_id).cloned());
                         let settings = &app.settings;
-                        let form_result = self.task_form.submit(&settings);
+                        let mut task_form = self.task_form();
+                        let form_result = match editing_task {
+                            Some(task) => task_form.submit_onto(task, settings),
+                            None => task_form.submit(settings),
+                        };
                         match form_result {
-                            Ok(new_taks) => {
-                                if let Some(task_id) = self.editing_task {
-                                    app.delete_task(task_id);
+                            Ok(new_task) => {
+                                if self.editing_task.is_some() {
+                                    app.update_task(new_task);
+                                } else {
+                                    app.add_task(new_task);
                                 }
-                                app.add_task(new_taks);
                                 return Ok(UIPage::AllTasks);
                             }
                             Err(e) => {
@@ -177,14 +339,12 @@ where
                     }
                     _ => {}
                 },
-                _ => match key.code {
-                    KeyCode::Esc => {
-                        self.input_mode = NewTaskInputMode::Normal;
-                    }
-                    KeyCode::Char(c) => self.add_char(c),
-                    KeyCode::Backspace => self.remove_char(),
-                    _ => {}
-                },
+                _ if action == Some(Action::NormalMode) => {
+                    self.input_mode = NewTaskInputMode::Normal;
+                }
+                _ => {
+                    self.current_field_mut().handle_key(key);
+                }
             }
         }
         Ok(UIPage::SamePage)
@@ -200,6 +360,9 @@ where
                     Constraint::Length(3),
                     Constraint::Length(3),
                     Constraint::Length(3),
+                    Constraint::Length(6),
+                    Constraint::Length(3),
+                    Constraint::Length(3),
                     Constraint::Length(3),
                     Constraint::Length(3),
                 ]
@@ -208,73 +371,141 @@ where
             .split(f.size());
 
         // Keybinds description paragraph
-        let keybinds = Paragraph::new(
-        "Press 'i' to enter input mode, 'q' to quit, 'j' and 'k' to move up and down, 'Enter' to save, 'Esc' to exit input mode, and 'b' to go back to the main screen. (*) Fields are required."
-    ).alignment(Alignment::Center)
-        .wrap(Wrap { trim: true });
+        let keybinds: Vec<String> = self
+            .keymaps
+            .new_task
+            .help()
+            .into_iter()
+            .map(|(keys, description)| format!("'{}' to {}", keys, description))
+            .collect();
+        let keybinds = format!("Press {}. (*) Fields are required.", keybinds.join(", "));
+        let keybinds = Paragraph::new(keybinds)
+            .alignment(Alignment::Center)
+            .wrap(Wrap { trim: true });
         f.render_widget(keybinds, chunks[0]);
 
-        // Name
-        let curr_text = self.task_form.name.clone();
-        let input = Paragraph::new(curr_text.as_ref())
-            .style(self.border_style(0))
-            .block(Block::default().borders(Borders::ALL).title("Name (*)"));
-        f.render_widget(input, chunks[1]);
-
-        // Date
-        let curr_text = self.task_form.date.clone();
-        let input = Paragraph::new(curr_text.as_ref())
-            .style(self.border_style(1))
-            .block(
-                Block::default()
-                    .borders(Borders::ALL)
-                    .title(format!("Date ({})", self.get_date_hint())),
-            );
-        f.render_widget(input, chunks[2]);
-
-        // Repeats
-        let curr_text = self.task_form.repeats.to_string();
-        let input = Paragraph::new(curr_text.as_ref())
-            .style(self.border_style(2))
-            .block(Block::default().borders(Borders::ALL).title(
-                "Repeats (Never | Daily | Weekly | Monthly | Yearly | Mon,Tue,Wed,Thu,Fri,Sat,Sun)",
-            ));
-        f.render_widget(input, chunks[3]);
-
-        // Description
-        let curr_text = self.task_form.description.clone();
-        let input = Paragraph::new(curr_text.as_ref())
-            .style(self.border_style(3))
-            .block(Block::default().borders(Borders::ALL).title("Description or URL"));
-        f.render_widget(input, chunks[4]);
+        // Fields, some of them side by side
+        let split_row = |area: Rect, num: u32| {
+            Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints(vec![Constraint::Ratio(1, num); num as usize])
+                .split(area)
+        };
+        let dates = split_row(chunks[2], 2);
+        let tags = split_row(chunks[6], 3);
+        let effort = split_row(chunks[7], 2);
+        let field_chunks = [
+            chunks[1], dates[0], dates[1], chunks[3], chunks[4], chunks[5], tags[0], tags[1],
+            tags[2], effort[0], effort[1],
+        ];
 
-        // Place cursor
-        match self.current_idx {
-            0 => f.set_cursor(
-                chunks[1].x + self.task_form.name.width() as u16 + 1,
-                chunks[1].y + 1,
-            ),
-            1 => f.set_cursor(
-                chunks[2].x + self.task_form.date.width() as u16 + 1,
-                chunks[2].y + 1,
-            ),
-            2 => f.set_cursor(
-                chunks[3].x + self.task_form.repeats.width() as u16 + 1,
-                chunks[3].y + 1,
-            ),
-            3 => f.set_cursor(
-                chunks[4].x + self.task_form.description.width() as u16 + 1,
-                chunks[4].y + 1,
+        let date_hint = self.get_date_hint();
+        let pick_keys = self.keymaps.new_task.keys_for(Action::Pick);
+        let titles = [
+            "Name (*)".to_string(),
+            format!("Date ({}, '{}' to pick)", date_hint, pick_keys),
+            format!("Scheduled, hidden until then ({})", date_hint),
+            format!(
+                "Repeats (Never | Daily | Weekly | Monthly | Yearly | Mon,Tue,...,Sun, '{}' to pick)",
+                pick_keys
             ),
-            _ => {}
+            "Description, Enter for a new line".to_string(),
+            "Links (URLs, file paths or mailto:, comma separated)".to_string(),
+            "Tags (comma separated)".to_string(),
+            "Project".to_string(),
+            "Priority (Low | Medium | High)".to_string(),
+            "Estimate (e.g. 45m, 1h30m)".to_string(),
+            "Reminders before due (e.g. 15m,1d)".to_string(),
+        ];
+        let task_form = self.task_form();
+        let app = self.app.borrow();
+        for (idx, title) in titles.into_iter().enumerate() {
+            let preview = task_form.preview(FORM_FIELDS[idx], &app.settings);
+            let border_style = match preview {
+                Err(_) => self.theme.error,
+                Ok(_) => self.theme.border,
+            };
+            let block = Block::default()
+                .borders(Borders::ALL)
+                .border_style(border_style)
+                .title(title);
+            self.inputs[idx].render(f, field_chunks[idx], block, self.border_style(idx));
+            self.render_preview(f, field_chunks[idx], preview);
         }
+        drop(app);
+
+        *self.field_areas.borrow_mut() = field_chunks.to_vec();
+
+        // Place cursor
+        self.inputs[self.current_idx].set_cursor(f, field_chunks[self.current_idx]);
 
         // Error message
         if let Some(error) = &self.error {
             let error = Paragraph::new(error.as_ref())
-                .style(Style::default().fg(Color::Red))
-                .block(Block::default().borders(Borders::ALL).title("Error"));
-            f.render_widget(error, chunks[5]);
+                .style(self.theme.error)
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .border_style(self.theme.border)
+                        .title("Error"),
+                );
+            f.render_widget(error, chunks[8]);
+        }
+
+        match &self.picker {
+            Some(Picker::Date(picker)) => picker.render(f, &self.theme),
+            Some(Picker::Repeat(picker)) => {
+                picker.render(f, &self.theme, &self.app.borrow().settings)
+            }
+            None => {}
+        }
+
+        if self.show_help {
+            help::render_help(f, &self.keymaps.new_task, &self.theme, &[]);
         }
     }
 }
+
+/// An input for each field of a form. The description takes several lines
+fn form_inputs(mut task_form: TaskForm) -> Vec<TextInput> {
+    form_fields(&mut task_form)
+        .into_iter()
+        .enumerate()
+        .map(|(idx, value)| match idx {
+            DESCRIPTION_FIELD => TextInput::multiline(value),
+            _ => TextInput::new(value),
+        })
+        .collect()
+}
+
+/// Which field each input is, for previews
+const FORM_FIELDS: [Field; 11] = [
+    Field::Name,
+    Field::Date,
+    Field::Scheduled,
+    Field::Repeats,
+    Field::Description,
+    Field::Links,
+    Field::Tags,
+    Field::Project,
+    Field::Priority,
+    Field::Estimate,
+    Field::Reminders,
+];
+
+/// The fields of a form in the order they're drawn
+fn form_fields(task_form: &mut TaskForm) -> [&mut String; 11] {
+    [
+        &mut task_form.name,
+        &mut task_form.date,
+        &mut task_form.scheduled,
+        &mut task_form.repeats,
+        &mut task_form.description,
+        &mut task_form.links,
+        &mut task_form.tags,
+        &mut task_form.project,
+        &mut task_form.priority,
+        &mut task_form.estimate,
+        &mut task_form.reminders,
+    ]
+}
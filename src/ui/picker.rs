@@ -0,0 +1,327 @@
+use crate::configuration::Settings;
+use crate::day_of_week::DayOfWeek;
+use crate::repeat::Repeat;
+use crate::status::Status;
+use crate::theme::Theme;
+use crate::utils;
+use chrono::{Datelike, Days, Local, Months, NaiveDate, NaiveTime, Timelike, Weekday};
+use crossterm::event::KeyCode;
+use tui::{
+    backend::Backend,
+    style::Modifier,
+    text::{Span, Spans},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+use super::help;
+
+/// What a key did to a picker
+#[derive(PartialEq)]
+pub enum PickerResult {
+    Pending,
+    Accepted,
+    Cancelled,
+}
+
+#[derive(PartialEq, Clone, Copy)]
+enum DateFocus {
+    Day,
+    Hour,
+    Minute,
+}
+
+/// A calendar to choose a day, and optionally a time, with the keyboard
+pub struct DatePicker {
+    day: NaiveDate,
+    /// `None` for the whole day
+    time: Option<NaiveTime>,
+    focus: DateFocus,
+}
+
+impl DatePicker {
+    /// Starts on the date in the field, which can be a day like `tomorrow`, or today when
+    /// it's empty or doesn't parse
+    pub fn new(value: &str, settings: &Settings) -> DatePicker {
+        let date = utils::parse_date(&value.trim().to_string(), settings)
+            .or_else(|_| utils::parse_relative_date(value, &utils::get_today(), settings));
+        let (day, time) = match date {
+            Ok(date) if utils::date_has_time(&date) => (date.date_naive(), Some(date.time())),
+            Ok(date) => (date.date_naive(), None),
+            Err(_) => (Local::now().date_naive(), None),
+        };
+        DatePicker {
+            day,
+            time,
+            focus: DateFocus::Day,
+        }
+    }
+
+    /// The chosen date in the input format the form parses. A time skipped by DST is kept
+    /// as it is, for the form to report
+    pub fn value(&self, settings: &Settings) -> String {
+        let formats = &settings.date_formats;
+        match self.time {
+            Some(time) => {
+                let datetime = self.day.and_time(time);
+                match utils::local_datetime(&datetime) {
+                    Ok(date) => date.format(&formats.input_datetime_format).to_string(),
+                    Err(_) => datetime.format(&formats.input_datetime_format).to_string(),
+                }
+            }
+            None => self.day.format(&formats.input_date_format).to_string(),
+        }
+    }
+
+    /// Arrows or hjkl move by day and week, H/L by month, Tab goes to the time
+    pub fn handle_key(&mut self, key: KeyCode) -> PickerResult {
+        match key {
+            KeyCode::Enter => return PickerResult::Accepted,
+            KeyCode::Esc | KeyCode::Char('q') => return PickerResult::Cancelled,
+            KeyCode::Tab => self.focus_time(1),
+            KeyCode::BackTab => self.focus_time(-1),
+            KeyCode::Backspace | KeyCode::Char('x') => {
+                self.time = None;
+                self.focus = DateFocus::Day;
+            }
+            KeyCode::Char('t') => self.day = Local::now().date_naive(),
+            _ if self.focus == DateFocus::Day => self.move_day(key),
+            _ => self.move_time(key),
+        }
+        PickerResult::Pending
+    }
+
+    fn move_day(&mut self, key: KeyCode) {
+        let day = match key {
+            KeyCode::Left | KeyCode::Char('h') => self.day.checked_sub_days(Days::new(1)),
+            KeyCode::Right | KeyCode::Char('l') => self.day.checked_add_days(Days::new(1)),
+            KeyCode::Up | KeyCode::Char('k') => self.day.checked_sub_days(Days::new(7)),
+            KeyCode::Down | KeyCode::Char('j') => self.day.checked_add_days(Days::new(7)),
+            KeyCode::PageUp | KeyCode::Char('H') => self.day.checked_sub_months(Months::new(1)),
+            KeyCode::PageDown | KeyCode::Char('L') => self.day.checked_add_months(Months::new(1)),
+            _ => None,
+        };
+        if let Some(day) = day {
+            self.day = day;
+        }
+    }
+
+    /// Up and down change the hour or minute, left and right switch between them
+    fn move_time(&mut self, key: KeyCode) {
+        let time = self.time.unwrap_or_default();
+        let (hour, minute) = (time.hour() as i32, time.minute() as i32);
+        let step = match key {
+            KeyCode::Up | KeyCode::Char('k') | KeyCode::Char('+') => 1,
+            KeyCode::Down | KeyCode::Char('j') | KeyCode::Char('-') => -1,
+            KeyCode::Left | KeyCode::Char('h') => return self.focus = DateFocus::Hour,
+            KeyCode::Right | KeyCode::Char('l') => return self.focus = DateFocus::Minute,
+            _ => return,
+        };
+        let (hour, minute) = match self.focus {
+            DateFocus::Hour => ((hour + step).rem_euclid(24), minute),
+            _ => (hour, (minute - minute % 5 + step * 5).rem_euclid(60)),
+        };
+        self.time = NaiveTime::from_hms_opt(hour as u32, minute as u32, 0);
+    }
+
+    /// Moves between the day, the hour and the minute, giving the task a time if it had none
+    fn focus_time(&mut self, delta: i32) {
+        let order = [DateFocus::Day, DateFocus::Hour, DateFocus::Minute];
+        let idx = order.iter().position(|&focus| focus == self.focus).unwrap() as i32;
+        self.focus = order[(idx + delta).rem_euclid(3) as usize];
+        if self.focus != DateFocus::Day && self.time.is_none() {
+            self.time = NaiveTime::from_hms_opt(9, 0, 0);
+        }
+    }
+
+    pub fn render<B: Backend>(&self, f: &mut Frame<B>, theme: &Theme) {
+        let today = Local::now().date_naive();
+        let first = self.day.with_day(1).unwrap();
+        let offset = first.weekday().num_days_from_monday() as u64;
+        let start = first.checked_sub_days(Days::new(offset)).unwrap();
+
+        let mut lines = vec![Spans::from(Span::styled(" Mo Tu We Th Fr Sa Su", theme.muted))];
+        for week in 0..6 {
+            let mut spans = vec![];
+            for weekday in 0..7 {
+                let day = start.checked_add_days(Days::new(week * 7 + weekday)).unwrap();
+                let style = if day == self.day && self.focus == DateFocus::Day {
+                    theme.selected.add_modifier(Modifier::REVERSED)
+                } else if day == self.day {
+                    theme.selected
+                } else if day.month() != self.day.month() {
+                    theme.muted
+                } else if day == today {
+                    theme.header
+                } else {
+                    theme.text
+                };
+                spans.push(Span::raw(" "));
+                spans.push(Span::styled(format!("{:>2}", day.day()), style));
+            }
+            lines.push(Spans::from(spans));
+        }
+
+        let focused = |focus| match self.focus == focus {
+            true => theme.selected.add_modifier(Modifier::REVERSED),
+            false => theme.text,
+        };
+        let time = match self.time {
+            Some(time) => vec![
+                Span::raw(" Time: "),
+                Span::styled(format!("{:02}", time.hour()), focused(DateFocus::Hour)),
+                Span::raw(":"),
+                Span::styled(format!("{:02}", time.minute()), focused(DateFocus::Minute)),
+            ],
+            None => vec![Span::raw(" Time: "), Span::styled("all day", theme.muted)],
+        };
+        lines.push(Spans::from(""));
+        lines.push(Spans::from(time));
+        lines.push(Spans::from(Span::styled(" Tab time · x all day", theme.muted)));
+        lines.push(Spans::from(Span::styled(" t today · Enter to pick", theme.muted)));
+
+        let title = first.format("%B %Y").to_string();
+        render_popup(f, theme, title, lines, 26);
+    }
+}
+
+/// The repeat options, then a checkbox per weekday
+const REPEAT_OPTIONS: [Repeat; 5] = [
+    Repeat::Never,
+    Repeat::Daily,
+    Repeat::Weekly,
+    Repeat::Monthly,
+    Repeat::Yearly,
+];
+
+const WEEKDAYS: [Weekday; 7] = [
+    Weekday::Mon,
+    Weekday::Tue,
+    Weekday::Wed,
+    Weekday::Thu,
+    Weekday::Fri,
+    Weekday::Sat,
+    Weekday::Sun,
+];
+
+/// A list of the ways a task repeats, with checkboxes for the days of the week
+pub struct RepeatPicker {
+    /// The row with the cursor: an option, then the weekdays
+    cursor: usize,
+    /// The chosen option, `None` when repeating on the checked weekdays
+    option: Option<usize>,
+    days: [bool; 7],
+}
+
+impl RepeatPicker {
+    pub fn new(value: &str) -> RepeatPicker {
+        let repeat = Repeat::parse_from_str(value).unwrap_or(Repeat::Never);
+        let mut days = [false; 7];
+        let option = match &repeat {
+            Repeat::DaysOfWeek(repeat_days) => {
+                for day in repeat_days {
+                    days[day.to_int() as usize - 1] = true;
+                }
+                None
+            }
+            repeat => REPEAT_OPTIONS.iter().position(|option| option == repeat),
+        };
+        RepeatPicker {
+            cursor: option.unwrap_or(REPEAT_OPTIONS.len()),
+            option,
+            days,
+        }
+    }
+
+    /// The choice in the format `Repeat::parse_from_str` reads
+    pub fn value(&self) -> String {
+        let repeat = match self.option {
+            Some(option) => REPEAT_OPTIONS[option].clone(),
+            None if !self.days.contains(&true) => Repeat::Never,
+            None => Repeat::DaysOfWeek(
+                WEEKDAYS
+                    .iter()
+                    .zip(self.days)
+                    .filter(|(_, checked)| *checked)
+                    .map(|(&day, _)| DayOfWeek::from_chrono(day))
+                    .collect(),
+            ),
+        };
+        repeat.to_string()
+    }
+
+    /// Up and down move, space picks an option or checks a day, Enter picks and closes
+    pub fn handle_key(&mut self, key: KeyCode) -> PickerResult {
+        let rows = REPEAT_OPTIONS.len() + WEEKDAYS.len();
+        match key {
+            KeyCode::Esc | KeyCode::Char('q') => return PickerResult::Cancelled,
+            KeyCode::Down | KeyCode::Char('j') => self.cursor = (self.cursor + 1).min(rows - 1),
+            KeyCode::Up | KeyCode::Char('k') => self.cursor = self.cursor.saturating_sub(1),
+            KeyCode::Char(' ') | KeyCode::Char('x') => self.toggle(),
+            KeyCode::Enter => {
+                // Enter on an option picks it, on a day it keeps the checked days
+                if self.cursor < REPEAT_OPTIONS.len() {
+                    self.toggle();
+                }
+                return PickerResult::Accepted;
+            }
+            _ => {}
+        }
+        PickerResult::Pending
+    }
+
+    fn toggle(&mut self) {
+        match self.cursor.checked_sub(REPEAT_OPTIONS.len()) {
+            None => self.option = Some(self.cursor),
+            Some(day) => {
+                self.days[day] = !self.days[day];
+                self.option = None;
+            }
+        }
+    }
+
+    pub fn render<B: Backend>(&self, f: &mut Frame<B>, theme: &Theme, settings: &Settings) {
+        let row_style = |row: usize| match row == self.cursor {
+            true => theme.selected,
+            false => theme.text,
+        };
+
+        let mut lines = vec![];
+        for (idx, option) in REPEAT_OPTIONS.iter().enumerate() {
+            let radio = if self.option == Some(idx) { "(•)" } else { "( )" };
+            lines.push(Spans::from(Span::styled(format!(" {} {}", radio, option), row_style(idx))));
+        }
+        let radio = if self.option.is_none() { "(•)" } else { "( )" };
+        lines.push(Spans::from(Span::styled(format!(" {} On these days:", radio), theme.text)));
+        for (idx, day) in WEEKDAYS.iter().enumerate() {
+            let status = if self.days[idx] { Status::Complete } else { Status::Todo };
+            let checkbox = settings.icons.get_status_icon(status);
+            let text = format!("     {} {}", checkbox.trim(), DayOfWeek::from_chrono(*day));
+            lines.push(Spans::from(Span::styled(text, row_style(REPEAT_OPTIONS.len() + idx))));
+        }
+        lines.push(Spans::from(""));
+        lines.push(Spans::from(Span::styled(" Space to check", theme.muted)));
+        lines.push(Spans::from(Span::styled(" Enter to pick", theme.muted)));
+
+        render_popup(f, theme, "Repeats".to_string(), lines, 26);
+    }
+}
+
+/// Draws lines in a bordered popup in the middle of the screen
+fn render_popup<B: Backend>(
+    f: &mut Frame<B>,
+    theme: &Theme,
+    title: String,
+    lines: Vec<Spans>,
+    width: u16,
+) {
+    let area = help::centered_rect(f.size(), width, lines.len() as u16 + 2);
+    let popup = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(theme.border)
+            .title(title),
+    );
+    f.render_widget(Clear, area);
+    f.render_widget(popup, area);
+}
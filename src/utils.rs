@@ -1,26 +1,45 @@
-use anyhow::{anyhow, Result};
-use chrono::{DateTime, Local, NaiveDate, TimeZone, Timelike};
+use anyhow::{anyhow, Context, Result};
+use chrono::{
+    DateTime, Datelike, Days, Duration, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Timelike,
+    Weekday,
+};
 
 use crate::app::App;
 use crate::configuration::Settings;
+use crate::status::Status;
 use crate::task::Task;
+use itertools::Itertools;
+use serde::Serialize;
 use std::fs;
+use std::io::BufWriter;
 
 pub fn load_tasks(file: &str) -> Vec<Task> {
-    let file = fs::read_to_string(file).expect("Unable to read file");
-    let mut tasks: Vec<Task> = serde_json::from_str(&file).expect("Unable to parse file");
+    try_load_tasks(file).expect("Unable to load tasks")
+}
+
+/// Reads and sorts the tasks, failing instead of panicking on a missing or bad file
+pub fn try_load_tasks(file: &str) -> Result<Vec<Task>> {
+    let file = fs::read_to_string(file).context("Unable to read file")?;
+    let mut tasks: Vec<Task> = serde_json::from_str(&file).context("Unable to parse file")?;
     tasks.sort_by(|a, b| a.date.cmp(&b.date));
-    tasks
+    Ok(tasks)
 }
 
 pub fn save_tasks(file: &str, app: &App) {
-    let file = fs::File::create(file).expect("Unable to create file");
-    serde_json::to_writer(file, &app.tasks).expect("Unable to write file");
+    write_json(file, &app.tasks);
 }
 
 pub fn save_settings(file: &str, settings: &Settings) {
-    let file = fs::File::create(file).expect("Unable to create file");
-    serde_json::to_writer(file, &settings).expect("Unable to write file");
+    write_json(file, settings);
+}
+
+/// Writes to a temporary file and renames it over `file`, so readers like the daemon
+/// never see a half written file
+pub fn write_json<T: Serialize + ?Sized>(file: &str, value: &T) {
+    let tmp_file = format!("{}.tmp", file);
+    let writer = BufWriter::new(fs::File::create(&tmp_file).expect("Unable to create file"));
+    serde_json::to_writer(writer, value).expect("Unable to write file");
+    fs::rename(&tmp_file, file).expect("Unable to write file");
 }
 
 pub fn date_has_time(date: &DateTime<Local>) -> bool {
@@ -51,12 +70,66 @@ pub fn date_to_input_str(dt: &DateTime<Local>, settings: &Settings) -> String {
     dt.format(format.as_str()).to_string()
 }
 
+/// "Today", "Tomorrow" or "Yesterday" for days close to now, the date otherwise
+pub fn relative_day_str(dt: &DateTime<Local>, settings: &Settings) -> String {
+    let today = Local::now().date_naive();
+    let day = dt.date_naive();
+    if day == today {
+        "Today".to_string()
+    } else if Some(day) == today.succ_opt() {
+        "Tomorrow".to_string()
+    } else if Some(day) == today.pred_opt() {
+        "Yesterday".to_string()
+    } else {
+        dt.format(&settings.date_formats.display_date_format).to_string()
+    }
+}
+
 pub fn get_today() -> DateTime<Local> {
-    let today = Local::now().date_naive().and_hms_opt(23, 59, 59).unwrap();
-    Local.from_local_datetime(&today).unwrap()
+    end_of_day(Local::now().date_naive())
+}
+
+/// A date and time in the local time zone. Errors when a DST change skips the time,
+/// and picks the earlier one when it happens twice
+pub fn local_datetime(datetime: &NaiveDateTime) -> Result<DateTime<Local>> {
+    Local
+        .from_local_datetime(datetime)
+        .earliest()
+        .ok_or_else(|| anyhow!("{} doesn't exist in the local time zone", datetime))
+}
+
+/// The first moment of a day, which is midnight unless a DST change skips it
+pub fn start_of_day(day: NaiveDate) -> DateTime<Local> {
+    let midnight = day.and_time(NaiveTime::default());
+    (0..24)
+        .find_map(|hour| local_datetime(&(midnight + Duration::hours(hour))).ok())
+        .unwrap_or_else(|| Local.from_utc_datetime(&midnight))
+}
+
+/// The last second of a day, 23:59:59 unless a DST change moves the next midnight
+pub fn end_of_day(day: NaiveDate) -> DateTime<Local> {
+    match day.succ_opt() {
+        Some(next_day) => start_of_day(next_day) - Duration::seconds(1),
+        None => Local.from_utc_datetime(&day.and_hms_opt(23, 59, 59).unwrap()),
+    }
 }
 
 pub fn parse_date(s: &String, settings: &Settings) -> Result<DateTime<Local>> {
+    parse_date_at(s, settings, end_of_day)
+}
+
+/// Like `parse_date`, but a date without a time is the start of that day. For scheduled
+/// dates, so the task shows up on the day it's scheduled for
+pub fn parse_start_date(s: &String, settings: &Settings) -> Result<DateTime<Local>> {
+    parse_date_at(s, settings, start_of_day)
+}
+
+/// Parses a date, turning dates without a time into a time with `time_of`
+fn parse_date_at(
+    s: &String,
+    settings: &Settings,
+    time_of: fn(NaiveDate) -> DateTime<Local>,
+) -> Result<DateTime<Local>> {
     let datetime_format = settings.date_formats.input_datetime_format.as_str();
     let date_format = settings.date_formats.input_date_format.as_str();
 
@@ -66,16 +139,128 @@ pub fn parse_date(s: &String, settings: &Settings) -> Result<DateTime<Local>> {
     if attempt_datetime.is_ok() {
         Ok(attempt_datetime.unwrap())
     } else if attempt_date.is_ok() {
-        let date = attempt_date.unwrap();
-        let datetime = date.and_hms_opt(23, 59, 59).unwrap();
-        Ok(Local.from_local_datetime(&datetime).unwrap())
+        Ok(time_of(attempt_date.unwrap()))
     } else {
         Err(anyhow!("Unable to parse date"))
     }
 }
 
-pub fn is_hyperlink(s: &String) -> bool {
-    s.starts_with("http://") || s.starts_with("https://")
+/// Whether a date is the start of its day, the way `parse_start_date` stores dates without a time
+pub fn is_start_of_day(date: &DateTime<Local>) -> bool {
+    date.time() == NaiveTime::default()
+}
+
+/// Formats a scheduled date for the input fields, leaving out the time at the start of the day
+pub fn start_date_to_input_str(dt: &DateTime<Local>, settings: &Settings) -> String {
+    match is_start_of_day(dt) {
+        true => dt.format(&settings.date_formats.input_date_format).to_string(),
+        false => date_to_input_str(dt, settings),
+    }
+}
+
+/// Formats a scheduled date for display, leaving out the time at the start of the day
+pub fn start_date_to_display_str(dt: &DateTime<Local>, settings: &Settings) -> String {
+    match is_start_of_day(dt) {
+        true => dt.format(&settings.date_formats.display_date_format).to_string(),
+        false => date_to_display_str(dt, settings),
+    }
+}
+
+/// Moves `date` according to a snooze spec, keeping its time of day.
+/// Accepts `today`, `tomorrow`, weekdays (`mon`, `next-mon`), offsets from
+/// the current date (`2d`, `-1d`, `1w`) and dates in the input format.
+pub fn parse_relative_date(
+    s: &str,
+    date: &DateTime<Local>,
+    settings: &Settings,
+) -> Result<DateTime<Local>> {
+    let spec = s.trim().to_lowercase();
+    let today = Local::now().date_naive();
+
+    let day = if spec == "today" {
+        today
+    } else if spec == "tomorrow" {
+        today.succ_opt().unwrap()
+    } else if let Ok(weekday) = spec.trim_start_matches("next-").parse::<Weekday>() {
+        let mut day = today.succ_opt().unwrap();
+        while day.weekday() != weekday {
+            day = day.succ_opt().unwrap();
+        }
+        day
+    } else if let Some(days) = parse_day_offset(&spec) {
+        let offset = Days::new(days.unsigned_abs());
+        let day = match days < 0 {
+            true => date.date_naive().checked_sub_days(offset),
+            false => date.date_naive().checked_add_days(offset),
+        };
+        day.ok_or_else(|| anyhow!("Unable to parse snooze date"))?
+    } else {
+        return parse_date(&s.trim().to_string(), settings)
+            .map_err(|_| anyhow!("Unable to parse snooze date"));
+    };
+
+    local_datetime(&day.and_time(date.time()))
+}
+
+fn parse_day_offset(spec: &str) -> Option<i64> {
+    let (number, multiplier) = if let Some(number) = spec.strip_suffix('d') {
+        (number, 1)
+    } else if let Some(number) = spec.strip_suffix('w') {
+        (number, 7)
+    } else {
+        return None;
+    };
+    let number: i64 = number.trim_start_matches('+').parse().ok()?;
+    number.checked_mul(multiplier)
+}
+
+/// URLs, `mailto:` addresses and file paths like `~/notes.md` or `./spec.pdf`
+pub fn is_link(s: &str) -> bool {
+    let prefixes = ["http://", "https://", "file://", "mailto:", "/", "~/", "./", "../"];
+    prefixes
+        .iter()
+        .any(|prefix| s.len() > prefix.len() && s.starts_with(prefix))
+        && !s.starts_with("//")
+}
+
+/// Every link in a text, in order. Markdown links and surrounding punctuation are handled
+pub fn extract_links(text: &str) -> Vec<String> {
+    let mut links: Vec<String> = vec![];
+    for word in text.split_whitespace() {
+        // `[text](url)` keeps the url
+        let word = match word.rfind("](") {
+            Some(idx) => &word[idx + 2..],
+            None => word,
+        };
+        let word = word.trim_start_matches(['(', '<', '[', '"', '\'', '*', '_', '`']);
+        let mut word = word.trim_end_matches(['>', ']', '"', '\'', '.', ',', ';', ':', '!', '?', '*', '_', '`']);
+        // Keep the closing parens of urls like `wiki/Rust_(language)`
+        while word.ends_with(')') && word.matches(')').count() > word.matches('(').count() {
+            word = word[..word.len() - 1].trim_end_matches(['.', ',', ';', ':', '!', '?']);
+        }
+
+        if is_link(word) && !links.iter().any(|link| link == word) {
+            links.push(word.to_string());
+        }
+    }
+    links
+}
+
+/// Links separated by commas or whitespace
+pub fn parse_links(s: &str) -> Vec<String> {
+    s.split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|link| !link.is_empty())
+        .map(|link| link.to_string())
+        .collect()
+}
+
+/// Opens a link with the default app, expanding `~` in paths
+pub fn open_link(link: &str) -> Result<()> {
+    let link = match (link.strip_prefix("~/"), std::env::var("HOME")) {
+        (Some(path), Ok(home)) => format!("{}/{}", home, path),
+        _ => link.to_string(),
+    };
+    open::that(&link).map_err(|e| anyhow!("Could not open {}: {}", link, e))
 }
 
 pub fn ellipsis_or_pad(s: &String, max_length: usize) -> String {
@@ -85,3 +270,108 @@ pub fn ellipsis_or_pad(s: &String, max_length: usize) -> String {
         format!("{:width$}", s, width = max_length)
     }
 }
+
+/// Parses durations like `90m`, `2h`, `1h30m` or `1d`
+pub fn parse_duration(s: &str) -> Result<Duration> {
+    let s = s.trim();
+    if s.is_empty() {
+        return Err(anyhow!("Duration cannot be empty"));
+    }
+
+    let mut seconds: i64 = 0;
+    let mut number = String::new();
+    for c in s.chars() {
+        if c.is_ascii_digit() {
+            number.push(c);
+            continue;
+        }
+        if c.is_whitespace() {
+            continue;
+        }
+
+        let unit = match c {
+            'd' => 24 * 60 * 60,
+            'h' => 60 * 60,
+            'm' => 60,
+            's' => 1,
+            _ => return Err(anyhow!("Unable to parse duration")),
+        };
+        seconds = add_duration_part(seconds, &number, unit)?;
+        number.clear();
+    }
+
+    // A bare number is a number of minutes
+    if !number.is_empty() {
+        seconds = add_duration_part(seconds, &number, 60)?;
+    }
+
+    // Durations are stored in milliseconds, which caps them well below i64 seconds
+    if seconds > Duration::max_value().num_seconds() {
+        return Err(anyhow!("Duration is too long"));
+    }
+    let total = Duration::seconds(seconds);
+
+    Ok(total)
+}
+
+/// Adds `number` units of `unit` seconds, failing instead of overflowing
+fn add_duration_part(seconds: i64, number: &str, unit: i64) -> Result<i64> {
+    let n: i64 = number.parse().map_err(|_| anyhow!("Unable to parse duration"))?;
+    n.checked_mul(unit)
+        .and_then(|part| seconds.checked_add(part))
+        .ok_or_else(|| anyhow!("Duration is too long"))
+}
+
+pub fn format_duration(duration: &Duration) -> String {
+    let minutes = duration.num_minutes();
+    let (hours, minutes) = (minutes / 60, minutes % 60);
+    match (hours, minutes) {
+        (0, m) => format!("{}m", m),
+        (h, 0) => format!("{}h", h),
+        (h, m) => format!("{}h {}m", h, m),
+    }
+}
+
+pub fn format_timer(duration: &Duration) -> String {
+    let seconds = duration.num_seconds();
+    format!(
+        "{}:{:02}:{:02}",
+        seconds / 3600,
+        (seconds % 3600) / 60,
+        seconds % 60
+    )
+}
+
+/// Estimated effort of the tasks, ignoring cancelled ones. Stops at the longest
+/// `Duration` rather than overflowing
+pub fn total_estimate<'a>(tasks: impl Iterator<Item = &'a Task>) -> Duration {
+    tasks
+        .filter(|t| t.status != Status::Cancelled)
+        .filter_map(|t| t.estimate)
+        .fold(Duration::zero(), |acc, e| {
+            acc.checked_add(&e).unwrap_or_else(Duration::max_value)
+        })
+}
+
+/// Describes a day's effort against the daily capacity and whether it's over capacity
+pub fn effort_summary(effort: &Duration, settings: &Settings) -> (String, bool) {
+    match settings.get_daily_capacity() {
+        Some(capacity) => {
+            let text = format!("{} / {}", format_duration(effort), format_duration(&capacity));
+            (text, *effort > capacity)
+        }
+        None => (format_duration(effort), false),
+    }
+}
+
+pub fn format_durations(durations: &[Duration]) -> String {
+    durations.iter().map(format_duration).join(", ")
+}
+
+pub fn parse_tags(s: &str) -> Vec<String> {
+    s.split(',')
+        .map(|t| t.trim())
+        .filter(|t| !t.is_empty())
+        .map(|t| t.to_string())
+        .collect()
+}
@@ -0,0 +1,77 @@
+use crate::configuration::Settings;
+use crate::query::Query;
+use crate::sort::{SortField, SortKey, SortOrder};
+use crate::task_form::TaskForm;
+use crate::utils;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+/// A saved way of listing tasks, e.g. `"work": {"filter": "tag:work", "sort": "priority,due"}`.
+/// Unset options fall back to the defaults. While a view is the active context,
+/// new tasks get its tags and project
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+pub struct View {
+    /// A filter expression, e.g. `tag:work and due<=+3d`
+    #[serde(default)]
+    pub filter: Option<String>,
+    /// A sort order, e.g. `priority,due`
+    #[serde(default)]
+    pub sort: Option<String>,
+    /// The sort key to group tasks by, ahead of the sort order
+    #[serde(default)]
+    pub group: Option<String>,
+    #[serde(default)]
+    pub show_complete: Option<bool>,
+    #[serde(default)]
+    pub show_waiting: Option<bool>,
+    /// Tags added to new tasks
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// The project of new tasks
+    #[serde(default)]
+    pub project: Option<String>,
+}
+
+impl View {
+    pub fn get_query(&self, settings: &Settings) -> Result<Option<Query>> {
+        match &self.filter {
+            Some(filter) if !filter.trim().is_empty() => Ok(Some(Query::parse(filter, settings)?)),
+            _ => Ok(None),
+        }
+    }
+
+    /// The view's sort order, or the configured one, with the group key first
+    pub fn get_sort_order(&self, settings: &Settings) -> Result<SortOrder> {
+        let mut sort_order = match &self.sort {
+            Some(sort) => SortOrder::parse_from_str(sort)?,
+            None => SortOrder::parse_from_str(&settings.sort).unwrap_or_default(),
+        };
+        if let Some(group) = &self.group {
+            let field = SortField::parse_from_str(group)?;
+            sort_order.keys.retain(|k| k.field != field);
+            let key = SortKey {
+                field,
+                descending: false,
+            };
+            sort_order.keys.insert(0, key);
+        }
+        Ok(sort_order)
+    }
+
+    /// Adds the view's tags and project to a new task
+    pub fn apply_context(&self, task_form: &mut TaskForm) {
+        let mut tags = utils::parse_tags(&task_form.tags);
+        for tag in self.tags.iter() {
+            if !tags.contains(tag) {
+                tags.push(tag.clone());
+            }
+        }
+        task_form.tags = tags.join(",");
+
+        if let Some(project) = &self.project {
+            if task_form.project.trim().is_empty() {
+                task_form.project = project.clone();
+            }
+        }
+    }
+}